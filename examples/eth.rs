@@ -1,9 +1,9 @@
-use dmbf::bitfield;
+use dmbf::{bitfield, MacAddr};
 
-#[bitfield(endianness = dmbf::Endianness::Msb0)]
+#[bitfield(byte_order = "big")]
 pub struct Eth {
-    dst: [u8; 6],
-    src: [u8; 6],
+    dst: MacAddr,
+    src: MacAddr,
     ty: u16,
 }
 
@@ -14,9 +14,12 @@ fn main() {
         0x08, 0x00, // type
     ];
 
-    let eth = Eth::from(&data);
+    let eth = Eth::new(&data).unwrap();
 
-    assert_eq!(eth.dst().get(), [0x00, 0x01, 0x02, 0x03, 0x04, 0x05]);
-    assert_eq!(eth.src().get(), [0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b]);
+    assert_eq!(
+        eth.dst().get(),
+        MacAddr::new(0x00, 0x01, 0x02, 0x03, 0x04, 0x05)
+    );
+    assert_eq!(eth.src().get().to_string(), "06:07:08:09:0a:0b");
     assert_eq!(eth.ty().get(), 0x0800);
 }
@@ -3,7 +3,7 @@ use std::net::Ipv4Addr;
 use dmbf::bitfield;
 
 /// IPv4 header
-#[bitfield]
+#[bitfield(byte_order = "big")]
 pub struct Ipv4 {
     /// Version (4 bits)
     ///
@@ -16,10 +16,9 @@ pub struct Ipv4 {
     dscp: u8,
     #[bitfield(bits = 2)]
     ecn: u8,
-    #[bitfield(from = |v: u16| u16::from_be(v), into = |v: u16| u16::to_be(v))]
     total_length: u16,
     identification: u16,
-    #[bitfield(bits = 3, from_into, from = |v: u16| v as u8)]
+    #[bitfield(bits = 3)]
     flags: u8,
     #[bitfield(bits = 13)]
     fragment_offset: u16,
@@ -27,19 +26,17 @@ pub struct Ipv4 {
     protocol: u8,
     checksum: u16,
 
-    #[bitfield(bits = 32, from = |v: u32| Ipv4Addr::from(u32::from_be(v)), into = |v: Ipv4Addr| u32::to_be(v.into()))]
     src: Ipv4Addr,
-    #[bitfield(bits = 32, from = |v: u32| Ipv4Addr::from(u32::from_be(v)), into = |v: Ipv4Addr| u32::to_be(v.into()))]
     dst: Ipv4Addr,
 }
 
 fn main() {
-    let data: [u8; 20] = [
+    let mut data: [u8; 20] = [
         0x45, 0x00, 0x00, 0x28, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0xb8, 0x0e, 0xc0, 0xa8, 0x00,
         0x01, 0xc0, 0xa8, 0x00, 0xc7,
     ];
 
-    let mut ipv4 = Ipv4::from(&data);
+    let mut ipv4 = Ipv4Mut::new(&mut data).unwrap();
 
     assert_eq!(ipv4.version().get(), 4);
 
@@ -51,16 +48,18 @@ fn main() {
 
     assert_eq!(ipv4.version().get(), 4);
 
+    let ipv4: Ipv4<&[u8]> = ipv4.into();
+
     assert_eq!(ipv4.ihl().get(), 5);
     assert_eq!(ipv4.dscp().get(), 0);
     assert_eq!(ipv4.ecn().get(), 0);
     assert_eq!(ipv4.total_length().get(), 40);
     assert_eq!(ipv4.identification().get(), 0);
-    assert_eq!(ipv4.flags().get(), 0);
-    assert_eq!(ipv4.fragment_offset().get(), 64);
+    assert_eq!(ipv4.flags().get(), 0b010);
+    assert_eq!(ipv4.fragment_offset().get(), 0);
     assert_eq!(ipv4.ttl().get(), 64);
     assert_eq!(ipv4.protocol().get(), 17);
-    assert_eq!(ipv4.checksum().get(), 0x0eb8);
+    assert_eq!(ipv4.checksum().get(), 0xb80e);
     assert_eq!(ipv4.src().get(), Ipv4Addr::new(192, 168, 0, 1));
     assert_eq!(ipv4.dst().get(), Ipv4Addr::new(192, 168, 0, 199));
 }
@@ -0,0 +1,130 @@
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Ident};
+
+pub fn expand(item: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &item.ident;
+
+    let data = match &item.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &item.ident,
+                "BitfieldEnum can only be derived for enums",
+            ))
+        }
+    };
+
+    // Underlay type from `#[repr(...)]`, `u8` by default
+    let mut underlay: Ident = format_ident!("u8");
+    for attr in item.attrs.iter().filter(|a| a.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                if ["u8", "u16", "u32", "u64"].contains(&ident.to_string().as_str()) {
+                    underlay = ident.clone();
+                    return Ok(());
+                }
+            }
+            Err(meta.error("BitfieldEnum supports `repr(u8 | u16 | u32 | u64)` only"))
+        })?;
+    }
+
+    let mut consts = Vec::new();
+    let mut known_idents = Vec::new();
+    let mut known_consts = Vec::new();
+    let mut unknown: Option<&Ident> = None;
+
+    let mut prev: Option<Ident> = None;
+    for (index, variant) in data.variants.iter().enumerate() {
+        match &variant.fields {
+            Fields::Unit => {}
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 && unknown.is_none() => {
+                if let Some((_, discriminant)) = &variant.discriminant {
+                    return Err(syn::Error::new_spanned(
+                        discriminant,
+                        "the catch-all variant cannot have a discriminant",
+                    ));
+                }
+                unknown = Some(&variant.ident);
+                continue;
+            }
+            fields => return Err(syn::Error::new_spanned(
+                fields,
+                "BitfieldEnum variants must be fieldless, except for one `Unknown(raw)` catch-all",
+            )),
+        }
+
+        // Named by position, since variant names may differ in case only
+        let const_name = format_ident!("__V{}", index);
+        let value = match (&variant.discriminant, &prev) {
+            (Some((_, expr)), _) => quote! { #expr },
+            (None, Some(prev)) => quote! { #prev + 1 },
+            (None, None) => quote! { 0 },
+        };
+        consts.push(quote! {
+            const #const_name: #underlay = #value;
+        });
+
+        known_idents.push(&variant.ident);
+        known_consts.push(const_name.clone());
+        prev = Some(const_name);
+    }
+
+    let (from_unknown, try_from_unknown, into_unknown) = match unknown {
+        Some(unknown) => (
+            quote! { raw => Self::#unknown(raw), },
+            quote! { raw => Ok(Self::#unknown(raw)), },
+            quote! { Self::#unknown(raw) => raw, },
+        ),
+        None => (
+            quote! {
+                raw => panic!("{}", dmbf::InvalidValue { raw: u64::from(raw) }),
+            },
+            quote! {
+                raw => Err(dmbf::InvalidValue { raw: u64::from(raw) }),
+            },
+            quote! {},
+        ),
+    };
+
+    Ok(quote! {
+        const _: () = {
+            #(#consts)*
+
+            impl dmbf::FieldSpec for #name {
+                type Underlay = #underlay;
+
+                const DEFAULT: Self::Underlay = 0;
+                const MASK: Self::Underlay = !0;
+                const SHIFT: u32 = 0;
+
+                type Target = Self;
+
+                #[inline]
+                fn from_underlay(v: Self::Underlay) -> Self::Target {
+                    match v {
+                        #(#known_consts => Self::#known_idents,)*
+                        #from_unknown
+                    }
+                }
+
+                #[inline]
+                fn into_underlay(v: Self::Target) -> Self::Underlay {
+                    match v {
+                        #(Self::#known_idents => #known_consts,)*
+                        #into_unknown
+                    }
+                }
+
+                #[inline]
+                fn try_from_underlay(
+                    v: Self::Underlay,
+                ) -> Result<Self::Target, dmbf::InvalidValue> {
+                    match v {
+                        #(#known_consts => Ok(Self::#known_idents),)*
+                        #try_from_unknown
+                    }
+                }
+            }
+        };
+    })
+}
@@ -1,25 +1,68 @@
 extern crate proc_macro;
 
+mod bitfield_enum;
+
 use std::ops::{Add, AddAssign};
 
 use convert_case::{Boundary, Casing};
 use darling::{ast::NestedMeta, FromAttributes, FromMeta};
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
-use syn::{parse_macro_input, Field, Ident, ItemStruct};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    ext::IdentExt, parse_macro_input, spanned::Spanned, visit_mut::VisitMut, Field, Ident,
+    ItemStruct,
+};
+
+/// Byte order of a field or of a whole hybrid group
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromMeta)]
+enum ByteOrderAttr {
+    #[darling(rename = "big")]
+    Big,
+    #[darling(rename = "little")]
+    Little,
+    #[darling(rename = "native")]
+    Native,
+}
+
+impl quote::ToTokens for ByteOrderAttr {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(match self {
+            ByteOrderAttr::Big => quote! { dmbf::ByteOrder::Big },
+            ByteOrderAttr::Little => quote! { dmbf::ByteOrder::Little },
+            ByteOrderAttr::Native => quote! { dmbf::ByteOrder::Native },
+        })
+    }
+}
+
+/// Bit numbering inside a hybrid group
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, FromMeta)]
+enum BitOrderAttr {
+    /// The first subfield takes the most significant bits
+    #[default]
+    #[darling(rename = "msb0")]
+    Msb0,
+    /// The first subfield takes the least significant bits
+    #[darling(rename = "lsb0")]
+    Lsb0,
+}
 
 #[derive(Clone, Debug, Default, FromMeta)]
 struct BitfieldAttr {
     /// Number of bits to use for the bitfield
     ///
     /// If not specified, the type of the field needs to implement `FieldSpec` trait
-    pub bits: Option<u8>,
+    pub bits: Option<u32>,
 
     /// Default value for the bitfield
     pub default: Option<syn::Expr>,
 
-    /// Endianness of the underlay type
-    pub endianness: Option<syn::Expr>,
+    /// Byte order of the underlay type
+    ///
+    /// Inside a hybrid group this applies to the whole group.
+    pub byte_order: Option<ByteOrderAttr>,
+
+    /// Bit numbering of a hybrid group
+    pub bit_order: Option<BitOrderAttr>,
 
     /// Use From/Into to convert the value
     #[darling(default)]
@@ -30,6 +73,131 @@ struct BitfieldAttr {
 
     /// Closure convert the value into the underlay type
     pub into: Option<syn::Expr>,
+
+    /// Close the hybrid field after this subfield, padding it to whole bytes
+    #[darling(default)]
+    pub pad: bool,
+
+    /// Pad every hybrid field that is not continued to whole bytes
+    #[darling(default)]
+    pub auto_pad: bool,
+
+    /// Reserved bits without public accessors
+    #[darling(default)]
+    pub reserved: bool,
+
+    /// Raw value a reserved field must hold, checked by `validate`
+    pub must_be: Option<syn::Expr>,
+
+    /// Raw value the field always holds, checked by the views' `new`
+    pub constant: Option<syn::Expr>,
+
+    /// The builder does not compile `build()` until the field is set
+    #[darling(default)]
+    pub required: bool,
+
+    /// Implement `Display` as a table of every field
+    #[darling(default)]
+    pub display: bool,
+
+    /// The field is another `#[bitfield]` struct
+    #[darling(default)]
+    pub nested: bool,
+
+    /// The last field holds the bytes after the `FieldBlock`
+    #[darling(default)]
+    pub payload: bool,
+
+    /// Length of the payload in bytes, computed from the other fields
+    pub len: Option<syn::Expr>,
+}
+
+impl BitfieldAttr {
+    /// Whether the value is converted through the `FieldSpec` of the target
+    fn via_field_spec(&self) -> bool {
+        self.from.is_none() && self.into.is_none() && !self.from_into
+    }
+
+    /// Reject combinations of arguments that cannot be expanded
+    ///
+    /// Arguments may be split over several `#[bitfield]` attributes, so this
+    /// runs once on their merge rather than on each of them.
+    fn check(self) -> darling::Result<Self> {
+        if self.bits == Some(0) {
+            return Err(darling::Error::custom("`bits` must be greater than 0"));
+        }
+        if self.from_into && (self.from.is_some() || self.into.is_some()) {
+            return Err(darling::Error::custom(
+                "`from_into` cannot be combined with `from` or `into`",
+            ));
+        }
+        if self.from.is_some() != self.into.is_some() {
+            return Err(darling::Error::custom(
+                "`from` and `into` must be given together",
+            ));
+        }
+        if self.must_be.is_some() && !self.reserved {
+            return Err(darling::Error::custom(
+                "`must_be` only applies to `reserved` fields",
+            ));
+        }
+        if self.must_be.is_some() && self.default.is_some() {
+            return Err(darling::Error::custom(
+                "`must_be` is the default of a reserved field and cannot be combined with `default`",
+            ));
+        }
+        if self.constant.is_some() && self.default.is_some() {
+            return Err(darling::Error::custom(
+                "`constant` is the default of the field and cannot be combined with `default`",
+            ));
+        }
+        if self.constant.is_some() && self.reserved {
+            return Err(darling::Error::custom(
+                "`constant` cannot be combined with `reserved`; use `must_be` instead",
+            ));
+        }
+        if self.required && (self.reserved || self.constant.is_some()) {
+            return Err(darling::Error::custom(
+                "`required` fields must be settable, so they cannot be `reserved` or `constant`",
+            ));
+        }
+        if self.nested
+            && (self.bits.is_some()
+                || self.default.is_some()
+                || self.byte_order.is_some()
+                || self.bit_order.is_some()
+                || !self.via_field_spec()
+                || self.reserved
+                || self.must_be.is_some()
+                || self.constant.is_some())
+        {
+            return Err(darling::Error::custom(
+                "`nested` fields take their layout from the nested struct and only accept `required`",
+            ));
+        }
+        if self.len.is_some() && !self.payload {
+            return Err(darling::Error::custom(
+                "`len` only applies to `payload` fields",
+            ));
+        }
+        if self.payload
+            && (self.bits.is_some()
+                || self.default.is_some()
+                || self.byte_order.is_some()
+                || self.bit_order.is_some()
+                || !self.via_field_spec()
+                || self.reserved
+                || self.must_be.is_some()
+                || self.constant.is_some()
+                || self.required
+                || self.nested)
+        {
+            return Err(darling::Error::custom(
+                "`payload` fields are plain bytes and only accept `len`",
+            ));
+        }
+        Ok(self)
+    }
 }
 
 impl Add<&BitfieldAttr> for BitfieldAttr {
@@ -39,10 +207,21 @@ impl Add<&BitfieldAttr> for BitfieldAttr {
         Self {
             bits: self.bits.or(rhs.bits),
             default: self.default.or(rhs.default.clone()),
-            endianness: self.endianness.or(rhs.endianness.clone()),
+            byte_order: self.byte_order.or(rhs.byte_order),
+            bit_order: self.bit_order.or(rhs.bit_order),
             from_into: self.from_into || rhs.from_into,
             from: self.from.or(rhs.from.clone()),
             into: self.into.or(rhs.into.clone()),
+            pad: self.pad || rhs.pad,
+            auto_pad: self.auto_pad || rhs.auto_pad,
+            reserved: self.reserved || rhs.reserved,
+            must_be: self.must_be.or(rhs.must_be.clone()),
+            constant: self.constant.or(rhs.constant.clone()),
+            required: self.required || rhs.required,
+            display: self.display || rhs.display,
+            nested: self.nested || rhs.nested,
+            payload: self.payload || rhs.payload,
+            len: self.len.or(rhs.len.clone()),
         }
     }
 }
@@ -61,58 +240,110 @@ impl AddAssign for BitfieldAttr {
     }
 }
 
+/// Arguments that only apply to the struct
+const STRUCT_ONLY: &[&str] = &["auto_pad", "display"];
+
+/// Arguments that only apply to fields
+const FIELD_ONLY: &[&str] = &[
+    "bits",
+    "default",
+    "from_into",
+    "from",
+    "into",
+    "pad",
+    "reserved",
+    "must_be",
+    "constant",
+    "required",
+    "nested",
+    "payload",
+    "len",
+];
+
+/// Reject arguments given on the struct that only apply to fields, or the
+/// other way round
+fn check_scope(metas: &[NestedMeta], on_struct: bool) -> darling::Result<()> {
+    let (keys, scope) = match on_struct {
+        true => (FIELD_ONLY, "fields"),
+        false => (STRUCT_ONLY, "the struct"),
+    };
+    let mut errors = darling::Error::accumulator();
+    for meta in metas {
+        let NestedMeta::Meta(meta) = meta else {
+            continue;
+        };
+        let path = meta.path();
+        if let Some(key) = keys.iter().find(|key| path.is_ident(key)) {
+            errors.push(
+                darling::Error::custom(format!("`{key}` only applies to {scope}")).with_span(path),
+            );
+        }
+    }
+    errors.finish()
+}
+
 impl FromAttributes for BitfieldAttr {
     fn from_attributes(attrs: &[syn::Attribute]) -> darling::Result<Self> {
+        let mut errors = darling::Error::accumulator();
         let mut final_attr = Self::default();
+        let mut last = None;
 
         for attr in attrs {
             if attr.path().is_ident("bitfield") {
+                last = Some(attr);
                 let meta = &attr.meta;
-                let attr = Self::from_meta(meta)?;
-
-                final_attr += attr;
+                if let syn::Meta::List(list) = meta {
+                    if let Some(metas) = errors.handle(
+                        NestedMeta::parse_meta_list(list.tokens.clone()).map_err(Into::into),
+                    ) {
+                        errors.handle(check_scope(&metas, false));
+                    }
+                }
+                if let Some(attr) =
+                    errors.handle(Self::from_meta(meta).map_err(|e| e.with_span(attr)))
+                {
+                    final_attr += attr;
+                }
             }
         }
+        errors.finish()?;
 
-        Ok(final_attr)
+        match last {
+            Some(last) => final_attr.check().map_err(|e| e.with_span(&last.meta)),
+            None => Ok(final_attr),
+        }
     }
 }
 
 fn gen_field_def(
     field: &Field,
-    // global attr, used to set endianness...
-    attr: BitfieldAttr,
-    bits: Option<u8>,
-    mask: u64,
-    shift: u8,
+    // field attr merged with the global attr
+    bitfield_attr: BitfieldAttr,
+    // byte order of the field, or of its hybrid group
+    byte_order: proc_macro2::TokenStream,
+    bits: Option<u32>,
+    // big-endian bytes of the mask, as wide as the underlay
+    mask: &[u8],
+    shift: u32,
     hybrid_field_name: &Option<Ident>,
 ) -> (
     Ident,
     Ident,
     proc_macro2::TokenStream,
     proc_macro2::TokenStream,
+    proc_macro2::TokenStream,
 ) {
     let field_name = field.ident.as_ref().unwrap().clone();
     let field_name_mut = format_ident!("{}_mut", field_name);
     let target_type = &field.ty;
-    let bitfield_attr = BitfieldAttr::from_attributes(&field.attrs).unwrap() + attr;
     let doc_attr = field.attrs.iter().filter(|a| a.path().is_ident("doc"));
 
-    let field_spec_name = format_ident!(
-        "{}Spec",
-        field_name
-            .to_string()
-            .with_boundaries(&[
-                Boundary::Acronym,
-                Boundary::DigitUpper,
-                Boundary::LowerUpper,
-                Boundary::Underscore,
-            ])
-            .to_case(convert_case::Case::UpperCamel)
-    );
+    let field_spec_name = spec_ident(&field_name);
+    // Suffixed so that it never shadows the target type, e.g. `mode: Mode`
     let field_name_uc = format_ident!(
-        "{}",
+        "{}Field",
         field_name
+            .unraw()
             .to_string()
             .with_boundaries(&[
                 Boundary::Acronym,
@@ -124,107 +355,140 @@ fn gen_field_def(
     );
 
     let (underlay, mask) = match bits {
-        Some(bits) => match bits {
-            8 => {
-                let mask = mask as u8;
-                (quote! { u8 }, quote! { #mask })
-            }
-            16 => {
-                let mask = mask as u16;
-                (quote! { u16 }, quote! { #mask as u16 })
-            }
-            24 => {
-                let mask: [u8; 3] = [
-                    ((mask >> 16) & 0xff) as u8,
-                    ((mask >> 8) & 0xff) as u8,
-                    (mask & 0xff) as u8,
-                ];
-                (quote! { [u8; 3] }, quote! { [#(#mask,)*] })
-            }
-            32 => {
-                let mask = mask as u32;
-                (quote! { u32 }, quote! { #mask })
-            }
-            40 => {
-                let mask: [u8; 5] = [
-                    ((mask >> 32) & 0xff) as u8,
-                    ((mask >> 24) & 0xff) as u8,
-                    ((mask >> 16) & 0xff) as u8,
-                    ((mask >> 8) & 0xff) as u8,
-                    (mask & 0xff) as u8,
-                ];
-                (quote! { [u8; 5] }, quote! { [#(#mask,)*] })
-            }
-            48 => {
-                let mask: [u8; 6] = [
-                    ((mask >> 40) & 0xff) as u8,
-                    ((mask >> 32) & 0xff) as u8,
-                    ((mask >> 24) & 0xff) as u8,
-                    ((mask >> 16) & 0xff) as u8,
-                    ((mask >> 8) & 0xff) as u8,
-                    (mask & 0xff) as u8,
-                ];
-                (quote! { [u8; 6] }, quote! { [#(#mask,)*] })
-            }
-            56 => {
-                let mask: [u8; 7] = [
-                    ((mask >> 48) & 0xff) as u8,
-                    ((mask >> 40) & 0xff) as u8,
-                    ((mask >> 32) & 0xff) as u8,
-                    ((mask >> 24) & 0xff) as u8,
-                    ((mask >> 16) & 0xff) as u8,
-                    ((mask >> 8) & 0xff) as u8,
-                    (mask & 0xff) as u8,
-                ];
-                (quote! { [u8; 7] }, quote! { [#(#mask,)*] })
-            }
-            64 => (quote! { u64 }, quote! { #mask }),
-            _ => unreachable!(),
-        },
+        Some(_) => gen_raw_value(mask),
         None => (
             quote! { <#target_type as dmbf::FieldSpec>::Underlay },
             quote! { <#target_type as dmbf::FieldSpec>::MASK },
         ),
     };
 
-    let default_value = bitfield_attr.default;
-    let default_value = match default_value {
-        Some(default_value) => quote! { #default_value },
+    let value_attr = [
+        ("default", &bitfield_attr.default),
+        ("must_be", &bitfield_attr.must_be),
+        ("constant", &bitfield_attr.constant),
+    ]
+    .into_iter()
+    .find_map(|(key, value)| Some((key, value.as_ref()?)));
+    // Evaluated here so that a value out of range fails even if unused
+    let value_check = match value_attr {
+        Some((_, value)) => quote_spanned! { value.span() =>
+            const _: () = {
+                let _ = <#field_spec_name as dmbf::FieldSpec>::DEFAULT;
+            };
+        },
+        None => quote! {},
+    };
+    let default_value = match value_attr {
+        Some((key, value)) => {
+            let value = gen_attr_value(target_type, &bitfield_attr, value);
+            let msg = format!("`{key}` of field `{field_name}` does not fit into its bits");
+            quote_spanned! { value.span() =>
+                match dmbf::konst::value(#value, Self::BITS, Self::SIGNED) {
+                    Some(v) => v,
+                    None => panic!(#msg),
+                }
+            }
+        }
         None => quote! { <#underlay as dmbf::FieldSpec>::DEFAULT },
     };
 
     // let mask = quote! { #mask as Self::Ux };
     let shift = quote! { #shift };
 
-    let endianness = match bitfield_attr.endianness {
-        Some(endianness) => quote! { #endianness },
-        None => quote! { dmbf::Endianness::Lsb0 },
+    let field_bits = match bitfield_attr.bits {
+        Some(bits) => quote! { #bits },
+        None => quote! { <#target_type as dmbf::FieldSpec>::BITS },
     };
 
+    // Checked conversions are only possible when the target is a `FieldSpec`
+    let via_field_spec = bitfield_attr.via_field_spec();
+
     let from_inner = if let Some(f) = bitfield_attr.from {
         quote! { (#f)(v) }
     } else if bitfield_attr.from_into {
         quote! { Self::Target::from(v) }
     } else {
-        quote! { <Self::Target as dmbf::FieldSpec>::from_underlay(v) }
+        quote! {
+            <#target_type as dmbf::FieldSpec>::from_underlay(dmbf::RawField::resize(
+                &v,
+                Self::BITS,
+                <#target_type as dmbf::FieldSpec>::SIGNED,
+            ))
+        }
     };
     let into_inner = if let Some(f) = bitfield_attr.into {
         quote! { (#f)(v) }
     } else if bitfield_attr.from_into {
         quote! { Self::Underlay::from(v) }
     } else {
-        quote! { <Self::Target as dmbf::FieldSpec>::into_underlay(v) }
+        quote! {
+            dmbf::RawField::resize(
+                &<#target_type as dmbf::FieldSpec>::into_underlay(v),
+                Self::BITS,
+                false,
+            )
+        }
+    };
+    // A `FieldSpec` target such as `be::U16` may convert to another type
+    let target = if via_field_spec {
+        quote! { <#target_type as dmbf::FieldSpec>::Target }
+    } else {
+        quote! { #target_type }
+    };
+
+    let (signed, try_methods) = if via_field_spec {
+        let signed = quote! { <#target_type as dmbf::FieldSpec>::SIGNED };
+        let try_methods = quote! {
+            #[inline]
+            fn try_from_underlay(
+                v: Self::Underlay,
+            ) -> Result<Self::Target, dmbf::InvalidValue> {
+                <#target_type as dmbf::FieldSpec>::try_from_underlay(dmbf::RawField::resize(
+                    &v,
+                    Self::BITS,
+                    <#target_type as dmbf::FieldSpec>::SIGNED,
+                ))
+            }
+
+            #[inline]
+            fn try_into_underlay(v: Self::Target) -> Option<Self::Underlay> {
+                let raw = <#target_type as dmbf::FieldSpec>::into_underlay(v);
+                if dmbf::RawField::fits(&raw, Self::BITS, Self::SIGNED) {
+                    Some(dmbf::RawField::resize(&raw, Self::BITS, false))
+                } else {
+                    None
+                }
+            }
+        };
+        (signed, try_methods)
+    } else {
+        (quote! { false }, quote! {})
+    };
+
+    // Wider `bits` than the target would silently drop the upper bits
+    let width_check = match bitfield_attr.bits {
+        Some(bits) if via_field_spec => {
+            let msg = format!("`bits` of field `{field_name}` is wider than its type");
+            quote_spanned! { target_type.span() =>
+                const _: () = assert!(#bits <= <#target_type as dmbf::FieldSpec>::BITS, #msg);
+            }
+        }
+        _ => quote! {},
     };
 
     let field_def = quote! {
+        #width_check
+        #value_check
         pub struct #field_spec_name;
         impl dmbf::FieldSpec for #field_spec_name {
             type Underlay = #underlay;
             const DEFAULT: Self::Underlay = #default_value;
             const MASK: Self::Underlay = #mask;
-            const SHIFT: u8 = #shift;
-            const ENDIANNESS: dmbf::Endianness = #endianness;
-            type Target = #target_type;
+            const SHIFT: u32 = #shift;
+            const BITS: u32 = #field_bits;
+            const SIGNED: bool = #signed;
+            const BYTE_ORDER: Option<dmbf::ByteOrder> = #byte_order;
+            type Target = #target;
             #[inline]
             fn from_underlay(v: Self::Underlay) -> Self::Target {
                 #from_inner
@@ -233,21 +497,35 @@ fn gen_field_def(
             fn into_underlay(v: Self::Target) -> Self::Underlay {
                 #into_inner
             }
+            #try_methods
         }
         #(#doc_attr)*
         pub type #field_name_uc = dmbf::Field<#field_spec_name>;
     };
 
+    // Reserved fields keep private accessors for `Default` and `validate`,
+    // constant fields a private setter for `Default`
+    let method_vis = if bitfield_attr.reserved {
+        quote! { #[allow(dead_code)] }
+    } else {
+        quote! { pub }
+    };
+    let method_vis_mut = if bitfield_attr.reserved || bitfield_attr.constant.is_some() {
+        quote! { #[allow(dead_code)] }
+    } else {
+        quote! { pub }
+    };
+
     let field_method = match hybrid_field_name {
         Some(hybrid_field_name) => {
             quote! {
                 #[inline]
-                pub fn #field_name(&self) -> &#field_name_uc {
+                #method_vis fn #field_name(&self) -> &#field_name_uc {
                     unsafe { &self.#hybrid_field_name.#field_name }
                 }
 
                 #[inline]
-                pub fn #field_name_mut(&mut self) -> &mut #field_name_uc {
+                #method_vis_mut fn #field_name_mut(&mut self) -> &mut #field_name_uc {
                     unsafe { &mut self.#hybrid_field_name.#field_name }
                 }
             }
@@ -255,35 +533,1082 @@ fn gen_field_def(
         None => {
             quote! {
                 #[inline]
-                pub fn #field_name(&self) -> &#field_name_uc {
+                #method_vis fn #field_name(&self) -> &#field_name_uc {
                     &self.#field_name
                 }
 
                 #[inline]
-                pub fn #field_name_mut(&mut self) -> &mut #field_name_uc {
+                #method_vis_mut fn #field_name_mut(&mut self) -> &mut #field_name_uc {
                     &mut self.#field_name
                 }
             }
         }
     };
 
-    (field_name, field_name_uc, field_def, field_method)
+    // Subfields are stored in the union of their hybrid field
+    let storage = hybrid_field_name.as_ref().unwrap_or(&field_name);
+    let field_name_str = field_name.unraw().to_string();
+    let reserved = bitfield_attr.reserved;
+    let doc = doc_string(&field.attrs);
+    let field_layout = quote! {
+        pub mod #field_name {
+            use super::super::{FieldBlock, #field_spec_name};
+
+            /// Offset of the field's storage in bytes
+            ///
+            /// For a subfield this is the offset of its hybrid field.
+            pub const BYTE_OFFSET: usize = core::mem::offset_of!(FieldBlock, #storage);
+
+            /// Shift of the field inside its storage, once loaded with its byte order
+            pub const BIT_OFFSET: u32 = <#field_spec_name as dmbf::FieldSpec>::SHIFT;
+
+            /// Width of the field in bits
+            pub const BIT_WIDTH: u32 = <#field_spec_name as dmbf::FieldSpec>::BITS;
+
+            /// Mask of the field inside its storage
+            pub const MASK: <#field_spec_name as dmbf::FieldSpec>::Underlay =
+                <#field_spec_name as dmbf::FieldSpec>::MASK;
+
+            /// Description of the field for `dmbf::Visit`
+            pub const INFO: dmbf::FieldInfo = dmbf::FieldInfo {
+                name: #field_name_str,
+                byte_offset: BYTE_OFFSET,
+                byte_len: core::mem::size_of::<<#field_spec_name as dmbf::FieldSpec>::Underlay>(),
+                bit_offset: BIT_OFFSET,
+                bit_width: BIT_WIDTH,
+                byte_order: dmbf::byte_order_of::<#field_spec_name>(),
+                reserved: #reserved,
+                doc: #doc,
+                fields: &[],
+            };
+        }
+    };
+
+    (
+        field_name,
+        field_name_uc,
+        field_def,
+        field_method,
+        field_layout,
+    )
+}
+
+/// Value of `default`, `must_be` or `constant` as a raw field for `konst::value`
+///
+/// Targets recognized by `const_target` take a value of their own type, such as
+/// `-1` for an `i8` or `[0; 3]` for a `[u8; 3]`. Other `FieldSpec` targets take
+/// their underlay, and fields with `from` / `into` an integer wide enough for
+/// their bits.
+fn gen_attr_value(
+    ty: &syn::Type,
+    attr: &BitfieldAttr,
+    value: &syn::Expr,
+) -> proc_macro2::TokenStream {
+    if !attr.via_field_spec() {
+        let raw = match attr.bits.unwrap_or(128) {
+            ..=8 => quote! { u8 },
+            9..=16 => quote! { u16 },
+            17..=32 => quote! { u32 },
+            33..=64 => quote! { u64 },
+            65..=128 => quote! { u128 },
+            bits => {
+                let len = bits.div_ceil(8) as usize;
+                quote! { [u8; #len] }
+            }
+        };
+        return quote_spanned! { value.span() => { let v: #raw = #value; v } };
+    }
+
+    match const_target(ty) {
+        Some(ConstTarget::Unsigned(ty) | ConstTarget::Signed(ty)) => {
+            quote_spanned! { value.span() => { let v: #ty = #value; v as u128 } }
+        }
+        Some(ConstTarget::Bool) => {
+            quote_spanned! { value.span() => { let v: bool = #value; v as u128 } }
+        }
+        Some(ConstTarget::Bytes(len)) => {
+            quote_spanned! { value.span() => { let v: [u8; #len] = #value; v } }
+        }
+        None => quote_spanned! { value.span() =>
+            { let v: <#ty as dmbf::FieldSpec>::Underlay = #value; v }
+        },
+    }
+}
+
+/// Paths of the view and the owned buffer of a nested struct
+///
+/// The field type names the view, e.g. `eth: Eth` or `eth: proto::Eth`, and
+/// the owned buffer sits next to it with a `Buf` suffix.
+fn nested_paths(field: &Field) -> syn::Result<(syn::Path, syn::Path)> {
+    let error = || {
+        syn::Error::new_spanned(
+            &field.ty,
+            "`nested` fields must name a `#[bitfield]` struct, e.g. `eth: Eth`",
+        )
+    };
+    let syn::Type::Path(syn::TypePath { qself: None, path }) = &field.ty else {
+        return Err(error());
+    };
+    let last = path.segments.last().ok_or_else(error)?;
+    if !last.arguments.is_empty() {
+        return Err(error());
+    }
+
+    let mut buf = path.clone();
+    buf.segments.last_mut().unwrap().ident = format_ident!("{}Buf", last.ident);
+    Ok((path.clone(), buf))
+}
+
+/// Accessors and layout of a nested struct
+///
+/// The nested struct is stored as its owned buffer, so its size counts toward
+/// the layout of this one. The accessors lend it out as a view.
+fn gen_nested_field(
+    field: &Field,
+    view: &syn::Path,
+    buf: &syn::Path,
+) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let field_name = field.ident.as_ref().unwrap();
+    let field_name_mut = format_ident!("{}_mut", field_name);
+    let field_name_str = field_name.unraw().to_string();
+    let doc_attr = field.attrs.iter().filter(|a| a.path().is_ident("doc"));
+    let doc = doc_string(&field.attrs);
+
+    let field_method = quote! {
+        #(#doc_attr)*
+        #[inline]
+        pub fn #field_name(&self) -> #view<&[u8]> {
+            #view { data: &self.#field_name.0 }
+        }
+
+        #[inline]
+        pub fn #field_name_mut(&mut self) -> #view<&mut [u8]> {
+            #view { data: &mut self.#field_name.0 }
+        }
+    };
+
+    // The paths are written relative to the struct, so the whole scope is
+    // imported rather than the few items used here
+    let field_layout = quote! {
+        pub mod #field_name {
+            use super::super::*;
+
+            /// Offset of the nested struct in bytes
+            pub const BYTE_OFFSET: usize = core::mem::offset_of!(FieldBlock, #field_name);
+
+            /// A nested struct always starts at a whole byte
+            pub const BIT_OFFSET: u32 = 0;
+
+            /// Width of the nested struct in bits
+            pub const BIT_WIDTH: u32 = (#buf::SIZE * 8) as u32;
+
+            /// Description of the field for `dmbf::Visit`
+            pub const INFO: dmbf::FieldInfo = dmbf::FieldInfo {
+                name: #field_name_str,
+                byte_offset: BYTE_OFFSET,
+                byte_len: #buf::SIZE,
+                bit_offset: BIT_OFFSET,
+                bit_width: BIT_WIDTH,
+                byte_order: dmbf::ByteOrder::Big,
+                reserved: false,
+                doc: #doc,
+                fields: #buf::FIELDS,
+            };
+        }
+    };
+
+    (field_method, field_layout)
+}
+
+/// Accessors of the trailing payload and the check of its length
+///
+/// The payload is not part of the `FieldBlock`, so the accessors live on the
+/// views. Fields named in `len` are read through their accessors and widened
+/// to `i128`, so that the expression may mix their types and a malformed
+/// header cannot overflow it. A negative length is reported as a
+/// `ValidationError` of the payload, a length past the buffer as a
+/// `LengthError`.
+fn gen_payload(
+    name: &Ident,
+    field: &Field,
+    len: Option<&syn::Expr>,
+    field_names: &[Ident],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let is_bytes = |ty: &syn::Type| match ty {
+        syn::Type::Slice(slice) => {
+            matches!(&*slice.elem, syn::Type::Path(p) if p.path.is_ident("u8"))
+        }
+        _ => false,
+    };
+    let bytes = match &field.ty {
+        syn::Type::Reference(reference) => {
+            reference.mutability.is_none() && is_bytes(&reference.elem)
+        }
+        ty => is_bytes(ty),
+    };
+    if !bytes {
+        return Err(syn::Error::new_spanned(
+            &field.ty,
+            "`payload` fields must be `[u8]` or `&[u8]`",
+        ));
+    }
+
+    let field_name = field.ident.as_ref().unwrap();
+    let field_name_mut = format_ident!("{}_mut", field_name);
+    let try_name = format_ident!("try_{}", field_name.unraw());
+    let try_name_mut = format_ident!("try_{}_mut", field_name.unraw());
+    let field_name_str = field_name.unraw().to_string();
+    let doc_attr = field.attrs.iter().filter(|a| a.path().is_ident("doc"));
+    let msg = format!("payload `{field_name}`: {{}}");
+
+    let end = match len {
+        Some(len) => {
+            let mut len = len.clone();
+            FieldReader(field_names).visit_expr_mut(&mut len);
+            quote! {
+                let len: i128 = #len;
+                let len = usize::try_from(len)
+                    .map_err(|_| dmbf::ValidationError { field: #field_name_str })?;
+                layout::SIZE.saturating_add(len)
+            }
+        }
+        None => quote! { self.data.as_ref().len() },
+    };
+
+    Ok(quote! {
+        impl<B: AsRef<[u8]>> #name<B> {
+            #(#doc_attr)*
+            ///
+            /// # Panics
+            ///
+            /// If the buffer does not hold the whole payload, which `new`
+            /// checks but a later change of the header may break.
+            #[inline]
+            pub fn #field_name(&self) -> &[u8] {
+                self.#try_name().unwrap_or_else(|e| panic!(#msg, e))
+            }
+
+            /// Checked access to the payload
+            ///
+            /// Fails if the buffer does not hold the whole payload or its
+            /// length is negative.
+            #[inline]
+            pub fn #try_name(&self) -> Result<&[u8], dmbf::Error> {
+                let range = self.payload_range()?;
+                Ok(&self.data.as_ref()[range])
+            }
+
+            #[inline]
+            fn payload_range(&self) -> Result<core::ops::Range<usize>, dmbf::Error> {
+                let end = { #end };
+                dmbf::LengthError::check(end, self.data.as_ref().len())?;
+                Ok(layout::SIZE..end)
+            }
+        }
+
+        impl<B: AsRef<[u8]> + AsMut<[u8]>> #name<B> {
+            /// Mutable access to the payload
+            ///
+            /// # Panics
+            ///
+            /// If the buffer does not hold the whole payload.
+            #[inline]
+            pub fn #field_name_mut(&mut self) -> &mut [u8] {
+                self.#try_name_mut().unwrap_or_else(|e| panic!(#msg, e))
+            }
+
+            /// Checked mutable access to the payload
+            ///
+            /// Fails if the buffer does not hold the whole payload or its
+            /// length is negative.
+            #[inline]
+            pub fn #try_name_mut(&mut self) -> Result<&mut [u8], dmbf::Error> {
+                let range = self.payload_range()?;
+                Ok(&mut self.data.as_mut()[range])
+            }
+        }
+    })
+}
+
+/// Replaces the names of fields in an expression by their values
+struct FieldReader<'a>(&'a [Ident]);
+
+impl VisitMut for FieldReader<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        if let syn::Expr::Path(syn::ExprPath {
+            qself: None, path, ..
+        }) = expr
+        {
+            if let Some(ident) = path.get_ident().filter(|ident| self.0.contains(ident)) {
+                *expr = syn::parse_quote! { (self.#ident().get() as i128) };
+                return;
+            }
+        }
+        syn::visit_mut::visit_expr_mut(self, expr);
+    }
+}
+
+/// Doc comment of a field, one line per `#[doc]` attribute
+///
+/// The single space that `///` leaves in front of each line is dropped.
+fn doc_string(attrs: &[syn::Attribute]) -> String {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Str(doc),
+                        ..
+                    }),
+                ..
+            }) => Some(doc.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_owned).unwrap_or(line))
+        .collect();
+    lines.join("\n")
+}
+
+/// Statement of `Visit::visit` passing the raw value of a field
+fn gen_visit(field_name: &Ident) -> proc_macro2::TokenStream {
+    quote! { self.#field_name().visit(&layout::#field_name::INFO, visitor); }
+}
+
+/// Statement of the owned buffer's `new` writing the default of a field
+fn gen_reset(field_name: &Ident) -> proc_macro2::TokenStream {
+    let spec = spec_ident(field_name);
+    quote! { dmbf::konst::reset::<#spec>(&mut bytes, layout::#field_name::BYTE_OFFSET); }
+}
+
+/// Name of the generated `FieldSpec` of a field
+fn spec_ident(field_name: &Ident) -> Ident {
+    format_ident!(
+        "{}Spec",
+        field_name
+            .unraw()
+            .to_string()
+            .with_boundaries(&[
+                Boundary::Acronym,
+                Boundary::DigitUpper,
+                Boundary::LowerUpper,
+                Boundary::Underscore,
+            ])
+            .to_case(convert_case::Case::UpperCamel)
+    )
+}
+
+/// Target that the const accessors can convert without calling trait methods
+enum ConstTarget {
+    Unsigned(Ident),
+    Signed(Ident),
+    Bool,
+    Bytes(syn::Expr),
+}
+
+/// Recognize integers, `be`/`le` integers, `bool` and byte arrays by name
+///
+/// A macro cannot resolve types, so this goes by how they are written: a
+/// primitive as a single identifier, and a `be`/`le` integer as `dmbf::be::U16`
+/// or, with the module imported, `be::U16`. Other spellings, such as an alias
+/// or an imported `U16`, are treated like any other `FieldSpec` type.
+fn const_target(ty: &syn::Type) -> Option<ConstTarget> {
+    match ty {
+        syn::Type::Array(array) => match &*array.elem {
+            syn::Type::Path(elem) if elem.path.is_ident("u8") => {
+                Some(ConstTarget::Bytes(array.len.clone()))
+            }
+            _ => None,
+        },
+        syn::Type::Path(path)
+            if path.qself.is_none() && path.path.segments.iter().all(|s| s.arguments.is_none()) =>
+        {
+            let segments: Vec<String> = path
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect();
+            let relative = path.path.leading_colon.is_none();
+            let is_order = |order: &String| order == "be" || order == "le";
+            match segments.as_slice() {
+                [name] if relative => {
+                    let ident = format_ident!("{}", name);
+                    match name.as_str() {
+                        "u8" | "u16" | "u32" | "u64" | "u128" => Some(ConstTarget::Unsigned(ident)),
+                        "i8" | "i16" | "i32" | "i64" | "i128" => Some(ConstTarget::Signed(ident)),
+                        "bool" => Some(ConstTarget::Bool),
+                        _ => None,
+                    }
+                }
+                [order, name] if relative && is_order(order) => ordered_target(name),
+                [krate, order, name] if krate == "dmbf" && is_order(order) => ordered_target(name),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Target of the `be`/`le` integer called `name`, e.g. `u16` for `U16`
+fn ordered_target(name: &str) -> Option<ConstTarget> {
+    let ident = format_ident!("{}", name.to_lowercase());
+    match name {
+        "U16" | "U32" | "U64" | "U128" => Some(ConstTarget::Unsigned(ident)),
+        "I16" | "I32" | "I64" | "I128" => Some(ConstTarget::Signed(ident)),
+        _ => None,
+    }
+}
+
+/// Const getter and `with_*` setter on the owned buffer
+///
+/// Only fields converted through the `FieldSpec` of a target recognized by
+/// `const_target` get them. Constant fields have no setter.
+fn gen_const_methods(field: &Field, attr: &BitfieldAttr) -> proc_macro2::TokenStream {
+    let Some(target) = const_target(&field.ty).filter(|_| attr.via_field_spec() && !attr.reserved)
+    else {
+        return quote! {};
+    };
+    let field_name = field.ident.as_ref().unwrap();
+    let with_name = format_ident!("with_{}", field_name);
+    let spec = spec_ident(field_name);
+    let offset = quote! { layout::#field_name::BYTE_OFFSET };
+    let raw = quote! { dmbf::konst::get::<#spec>(&self.0, #offset) };
+
+    let (ty, get, set) = match target {
+        ConstTarget::Unsigned(ty) => (
+            quote! { #ty },
+            quote! { #raw as #ty },
+            quote! { dmbf::konst::set::<#spec>(&mut self.0, #offset, v as u128) },
+        ),
+        ConstTarget::Signed(ty) => (
+            quote! { #ty },
+            quote! { dmbf::konst::sign_extend(#raw, <#spec as dmbf::FieldSpec>::BITS) as #ty },
+            quote! { dmbf::konst::set::<#spec>(&mut self.0, #offset, v as u128) },
+        ),
+        ConstTarget::Bool => (
+            quote! { bool },
+            quote! { #raw != 0 },
+            quote! { dmbf::konst::set::<#spec>(&mut self.0, #offset, v as u128) },
+        ),
+        ConstTarget::Bytes(len) => (
+            quote! { [u8; #len] },
+            quote! { dmbf::konst::get_bytes::<#spec, { #len }>(&self.0, #offset) },
+            quote! { dmbf::konst::set_bytes::<#spec, { #len }>(&mut self.0, #offset, v) },
+        ),
+    };
+
+    let setter = match attr.constant {
+        Some(_) => quote! {},
+        None => quote! {
+            /// Set the field, truncating the value; usable in const context
+            #[inline]
+            pub const fn #with_name(mut self, v: #ty) -> Self {
+                #set;
+                self
+            }
+        },
+    };
+
+    quote! {
+        /// Get the field; usable in const context
+        #[inline]
+        pub const fn #field_name(&self) -> #ty {
+            #get
+        }
+
+        #setter
+    }
+}
+
+/// Type of a field shown by `Debug`, `Display` and serde
+enum Shown {
+    /// The `Field` alias of a plain field or subfield
+    Field(Ident),
+    /// The view and the owned buffer of a nested struct
+    Nested(syn::Path, syn::Path),
+}
+
+impl Shown {
+    /// Type that has to implement a trait for the field to be shown with it
+    fn bound(&self, field_name: &Ident) -> proc_macro2::TokenStream {
+        match self {
+            Shown::Field(_) => {
+                let spec = spec_ident(field_name);
+                quote! { <#spec as dmbf::FieldSpec>::Target }
+            }
+            Shown::Nested(view, _) => quote! { #view<&'static [u8]> },
+        }
+    }
+}
+
+/// `Debug` of the `FieldBlock` and the views, and the opt-in `Display`
+///
+/// `Debug` prints the decoded value of every field but the reserved ones. It
+/// is only implemented if all of their targets implement `Debug`; the
+/// higher-ranked bounds keep the compiler from rejecting the impl otherwise.
+fn gen_fmt_impls(
+    name: &Ident,
+    fields: &[(Ident, Shown)],
+    display: bool,
+) -> proc_macro2::TokenStream {
+    let name_str = name.unraw().to_string();
+    let field_strs: Vec<String> = fields.iter().map(|(n, _)| n.unraw().to_string()).collect();
+    let field_bounds: Vec<proc_macro2::TokenStream> = fields
+        .iter()
+        .map(|(n, shown)| match shown {
+            Shown::Field(alias) => quote! { #alias },
+            Shown::Nested(..) => shown.bound(n),
+        })
+        .collect();
+    // Nested structs are shown through their view, which is returned by value
+    let field_values: Vec<proc_macro2::TokenStream> = fields
+        .iter()
+        .map(|(n, shown)| match shown {
+            Shown::Field(_) => quote! { self.#n() },
+            Shown::Nested(..) => quote! { &self.#n() },
+        })
+        .collect();
+    let field_bits = fields.iter().map(|(n, shown)| match shown {
+        Shown::Field(_) => {
+            let spec = spec_ident(n);
+            quote! { <#spec as dmbf::FieldSpec>::BITS }
+        }
+        Shown::Nested(view, _) => quote! { (#view::<&[u8]>::SIZE * 8) as u32 },
+    });
+
+    let display_impls = if display {
+        quote! {
+            impl core::fmt::Display for FieldBlock {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    dmbf::display::write_table(f, &[
+                        #((
+                            #field_strs,
+                            #field_bits,
+                            format!("{:?}", #field_values),
+                        ),)*
+                    ])
+                }
+            }
+
+            impl<B: AsRef<[u8]>> core::fmt::Display for #name<B> {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    core::fmt::Display::fmt(&**self, f)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        impl core::fmt::Debug for FieldBlock
+        where
+            #(for<'__x> #field_bounds: core::fmt::Debug,)*
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(#name_str)
+                    #(.field(#field_strs, #field_values))*
+                    .finish()
+            }
+        }
+
+        impl<B: AsRef<[u8]>> core::fmt::Debug for #name<B>
+        where
+            for<'__x> FieldBlock: core::fmt::Debug,
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(&**self, f)
+            }
+        }
+
+        #display_impls
+    }
+}
+
+/// `Serialize` of the `FieldBlock`, the views and the owned buffer, and
+/// `Deserialize` of the owned buffer
+///
+/// Only emitted with the `serde` feature, which `dmbf` forwards to this crate
+/// together with its own re-export of `serde`. Fields are serialized as a
+/// struct of their decoded targets, leaving out the reserved ones, and nested
+/// structs as their view. Deserializing starts from the defaults, sets every
+/// given field through `try_set` and then checks the constant fields.
+fn gen_serde_impls(
+    name: &Ident,
+    name_buf: &Ident,
+    fields: &[(Ident, Shown)],
+) -> proc_macro2::TokenStream {
+    if !cfg!(feature = "serde") {
+        return quote! {};
+    }
+
+    let serde = quote! { dmbf::__private::serde };
+    let name_str = name.unraw().to_string();
+    let field_strs: Vec<String> = fields.iter().map(|(n, _)| n.unraw().to_string()).collect();
+    let ser_bounds = fields.iter().map(|(n, shown)| shown.bound(n));
+    let de_bounds: Vec<proc_macro2::TokenStream> = fields
+        .iter()
+        .map(|(n, shown)| match shown {
+            Shown::Field(_) => shown.bound(n),
+            Shown::Nested(_, buf) => quote! { #buf },
+        })
+        .collect();
+    let ser_fields = fields
+        .iter()
+        .zip(&field_strs)
+        .map(|((n, shown), field_str)| match shown {
+            Shown::Field(_) => {
+                let msg = format!("field `{n}`: {{}}");
+                quote! {
+                    let value = self
+                        .#n()
+                        .try_get()
+                        .map_err(|e| S::Error::custom(format_args!(#msg, e)))?;
+                    state.serialize_field(#field_str, &value)?;
+                }
+            }
+            Shown::Nested(..) => quote! {
+                state.serialize_field(#field_str, &self.#n())?;
+            },
+        });
+    let de_fields = fields.iter().map(|(n, shown)| {
+        let n_mut = format_ident!("{}_mut", n);
+        match shown {
+            Shown::Field(_) => {
+                let msg = format!("field `{n}`: {{}}");
+                quote! {
+                    view.#n_mut()
+                        .try_set(map.next_value()?)
+                        .map_err(|e| A::Error::custom(format_args!(#msg, e)))?
+                }
+            }
+            Shown::Nested(_, buf) => quote! {
+                view.#n_mut()
+                    .data
+                    .copy_from_slice(&map.next_value::<#buf>()?.0)
+            },
+        }
+    });
+    let len = fields.len();
+    let expecting = format!("struct {name}");
+
+    quote! {
+        impl #serde::Serialize for FieldBlock
+        where
+            #(for<'__x> #ser_bounds: #serde::Serialize,)*
+        {
+            fn serialize<S: #serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use #serde::ser::{Error as _, SerializeStruct as _};
+
+                let mut state = serializer.serialize_struct(#name_str, #len)?;
+                #(#ser_fields)*
+                state.end()
+            }
+        }
+
+        impl<B: AsRef<[u8]>> #serde::Serialize for #name<B>
+        where
+            for<'__x> FieldBlock: #serde::Serialize,
+        {
+            fn serialize<S: #serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                #serde::Serialize::serialize(&**self, serializer)
+            }
+        }
+
+        impl #serde::Serialize for #name_buf
+        where
+            for<'__x> FieldBlock: #serde::Serialize,
+        {
+            fn serialize<S: #serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                #serde::Serialize::serialize(&*self.as_view(), serializer)
+            }
+        }
+
+        impl<'de> #serde::Deserialize<'de> for #name_buf
+        where
+            #(#de_bounds: #serde::Deserialize<'de>,)*
+        {
+            fn deserialize<D: #serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                const NAMES: &[&str] = &[#(#field_strs),*];
+
+                struct Visitor;
+
+                impl<'de> #serde::de::Visitor<'de> for Visitor
+                where
+                    #(#de_bounds: #serde::Deserialize<'de>,)*
+                {
+                    type Value = #name_buf;
+
+                    fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                        f.write_str(#expecting)
+                    }
+
+                    fn visit_map<A: #serde::de::MapAccess<'de>>(
+                        self,
+                        mut map: A,
+                    ) -> Result<Self::Value, A::Error> {
+                        use #serde::de::Error as _;
+
+                        let mut buf = #name_buf::new();
+                        let mut view = buf.as_view_mut();
+                        while let Some(key) = map.next_key::<std::string::String>()? {
+                            match key.as_str() {
+                                #(#field_strs => #de_fields,)*
+                                _ => return Err(A::Error::unknown_field(&key, NAMES)),
+                            }
+                        }
+                        view.check_constants().map_err(A::Error::custom)?;
+                        Ok(buf)
+                    }
+                }
+
+                deserializer.deserialize_struct(#name_str, NAMES, Visitor)
+            }
+        }
+    }
+}
+
+/// Builder of the owned buffer
+///
+/// Every `required` field adds a type parameter that turns from
+/// `dmbf::builder::Unset` into `dmbf::builder::Set` when the field is set.
+/// `build()` needs all of them to be `Set`.
+///
+/// A nested struct is set from its owned buffer, given as the third element.
+fn gen_builder(
+    name_builder: &Ident,
+    name_buf: &Ident,
+    fields: &[(Ident, bool, Option<syn::Path>)],
+) -> proc_macro2::TokenStream {
+    let params: Vec<Ident> = (0..fields.iter().filter(|(_, required, _)| *required).count())
+        .map(|i| format_ident!("__R{}", i))
+        .collect();
+
+    let mut required_index = 0;
+    let setters = fields.iter().map(|(field_name, required, nested)| {
+        let field_name_mut = format_ident!("{}_mut", field_name);
+        let (ty, set) = match nested {
+            Some(buf) => (
+                quote! { #buf },
+                quote! {
+                    self.buf.as_view_mut().#field_name_mut().data.copy_from_slice(&v.0);
+                },
+            ),
+            None => {
+                let spec = spec_ident(field_name);
+                let msg = format!("field `{field_name}`: {{}}");
+                (
+                    quote! { <#spec as dmbf::FieldSpec>::Target },
+                    quote! {
+                        let mut view = self.buf.as_view_mut();
+                        if cfg!(debug_assertions) {
+                            view.#field_name_mut()
+                                .try_set(v)
+                                .unwrap_or_else(|e| panic!(#msg, e));
+                        } else {
+                            view.#field_name_mut().set(v);
+                        }
+                    },
+                )
+            }
+        };
+
+        if *required {
+            let index = required_index;
+            required_index += 1;
+            let set_params = params.iter().enumerate().map(|(i, p)| {
+                if i == index {
+                    quote! { dmbf::builder::Set }
+                } else {
+                    quote! { #p }
+                }
+            });
+            quote! {
+                #[inline]
+                pub fn #field_name(mut self, v: #ty) -> #name_builder<#(#set_params),*> {
+                    #set
+                    #name_builder {
+                        buf: self.buf,
+                        _required: core::marker::PhantomData,
+                    }
+                }
+            }
+        } else {
+            quote! {
+                #[inline]
+                pub fn #field_name(mut self, v: #ty) -> Self {
+                    #set
+                    self
+                }
+            }
+        }
+    });
+
+    quote! {
+        /// Builder of an owned buffer, starting from the default of every field
+        ///
+        /// In debug builds the setters panic if a value does not fit into its
+        /// field, in release builds it is truncated.
+        #[derive(Debug, Clone, Copy)]
+        pub struct #name_builder<#(#params = dmbf::builder::Unset),*> {
+            buf: #name_buf,
+            _required: core::marker::PhantomData<(#(#params,)*)>,
+        }
+
+        impl #name_builder {
+            /// Start from the default of every field
+            #[inline]
+            pub const fn new() -> Self {
+                Self {
+                    buf: #name_buf::new(),
+                    _required: core::marker::PhantomData,
+                }
+            }
+        }
+
+        impl Default for #name_builder {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<#(#params),*> #name_builder<#(#params),*> {
+            #(#setters)*
+
+            /// Finish building
+            ///
+            /// This only compiles once every `required` field is set.
+            #[inline]
+            pub fn build(self) -> #name_buf
+            where
+                #(#params: dmbf::builder::IsSet,)*
+            {
+                self.buf
+            }
+        }
+
+        impl #name_buf {
+            /// Start building an owned buffer from the default of every field
+            #[inline]
+            pub fn builder() -> #name_builder {
+                #name_builder::new()
+            }
+        }
+    }
+}
+
+/// Check of a field against the value given by `must_be` or `constant`
+///
+/// The value is the `DEFAULT` of the field, converted to its underlay.
+fn gen_value_check(field: &Field, value: Option<&syn::Expr>) -> Option<proc_macro2::TokenStream> {
+    value?;
+    let field_name = field.ident.as_ref().unwrap();
+    let field_str = field_name.unraw().to_string();
+    let spec = spec_ident(field_name);
+    Some(quote! {
+        if self.#field_name().raw() != <#spec as dmbf::FieldSpec>::DEFAULT {
+            return Err(dmbf::ValidationError { field: #field_str });
+        }
+    })
+}
+
+/// Byte order of a field or hybrid group as an `Option<dmbf::ByteOrder>`
+///
+/// An explicit `byte_order` wins, then the first target type with a byte order
+/// of its own, then the `byte_order` of the struct.
+fn gen_byte_order(
+    explicit: Option<ByteOrderAttr>,
+    types: &[&syn::Type],
+    fallback: Option<ByteOrderAttr>,
+) -> proc_macro2::TokenStream {
+    if let Some(byte_order) = explicit {
+        return quote! { Some(#byte_order) };
+    }
+
+    let mut byte_order = match fallback {
+        Some(byte_order) => quote! { Some(#byte_order) },
+        None => quote! { None },
+    };
+    for ty in types.iter().rev() {
+        byte_order = quote! {
+            match <#ty as dmbf::FieldSpec>::BYTE_ORDER {
+                Some(byte_order) => Some(byte_order),
+                None => #byte_order,
+            }
+        };
+    }
+    byte_order
+}
+
+/// Check that the field types of a hybrid group agree on their byte order
+///
+/// Without a `byte_order` attribute the group takes the order of its types, so
+/// two types asking for different orders cannot both be honoured.
+fn gen_byte_order_check(fields: &[&Field]) -> proc_macro2::TokenStream {
+    let mut checks = quote! {};
+    for (i, field) in fields.iter().enumerate() {
+        let ty = &field.ty;
+        for other in &fields[..i] {
+            let other_ty = &other.ty;
+            let msg = format!(
+                "conflicting byte orders of `{}` and `{}` in hybrid group; set `byte_order` on the group",
+                other.ident.as_ref().unwrap(),
+                field.ident.as_ref().unwrap(),
+            );
+            checks.extend(quote_spanned! { ty.span() =>
+                const _: () = assert!(
+                    match (
+                        <#other_ty as dmbf::FieldSpec>::BYTE_ORDER,
+                        <#ty as dmbf::FieldSpec>::BYTE_ORDER,
+                    ) {
+                        (Some(a), Some(b)) => a.resolve() as u8 == b.resolve() as u8,
+                        _ => true,
+                    },
+                    #msg
+                );
+            });
+        }
+    }
+    checks
+}
+
+/// Bits, attributes and definition of a subfield in a hybrid group
+type Subfield<'a> = (u32, &'a BitfieldAttr, &'a Field);
+
+/// Byte order and bit order of a hybrid group
+///
+/// Both may be set on any subfield, but every subfield that sets one has to
+/// agree with the others.
+fn gen_group_attr(subfields: &[Subfield]) -> syn::Result<BitfieldAttr> {
+    let mut group_attr = BitfieldAttr::default();
+    for (_, attr, field) in subfields {
+        let conflict = |arg: &str| {
+            syn::Error::new_spanned(
+                &field.ident,
+                format!("conflicting `{arg}` in hybrid group; all subfields must agree"),
+            )
+        };
+        if let (Some(group), Some(own)) = (group_attr.byte_order, attr.byte_order) {
+            if group != own {
+                return Err(conflict("byte_order"));
+            }
+        }
+        if let (Some(group), Some(own)) = (group_attr.bit_order, attr.bit_order) {
+            if group != own {
+                return Err(conflict("bit_order"));
+            }
+        }
+        group_attr.byte_order = group_attr.byte_order.or(attr.byte_order);
+        group_attr.bit_order = group_attr.bit_order.or(attr.bit_order);
+    }
+    Ok(group_attr)
+}
+
+/// Big-endian bytes of a mask with `bits` ones at `shift` in a `total` bit value
+fn gen_mask(total: u32, bits: u32, shift: u32) -> Vec<u8> {
+    let len = total.div_ceil(8) as usize;
+    let mut mask = vec![0_u8; len];
+    for bit in shift..shift + bits {
+        mask[len - 1 - (bit / 8) as usize] |= 1 << (bit % 8);
+    }
+    mask
+}
+
+/// Underlay type and value for a raw field given as big-endian bytes
+///
+/// Widths of 1, 2, 4, 8 and 16 bytes map to `u8`..`u128`, any other width to
+/// `[u8; N]`.
+fn gen_raw_value(be: &[u8]) -> (proc_macro2::TokenStream, proc_macro2::TokenStream) {
+    let value = be.iter().fold(0_u128, |acc, b| (acc << 8) | *b as u128);
+    match be.len() {
+        1 => (quote! { u8 }, {
+            let v = value as u8;
+            quote! { #v }
+        }),
+        2 => (quote! { u16 }, {
+            let v = value as u16;
+            quote! { #v }
+        }),
+        4 => (quote! { u32 }, {
+            let v = value as u32;
+            quote! { #v }
+        }),
+        8 => (quote! { u64 }, {
+            let v = value as u64;
+            quote! { #v }
+        }),
+        16 => (quote! { u128 }, quote! { #value }),
+        n => (quote! { [u8; #n] }, quote! { [#(#be,)*] }),
+    }
 }
 
 #[proc_macro_attribute]
 pub fn bitfield(attr: TokenStream, input: TokenStream) -> TokenStream {
-    let global_attr = NestedMeta::parse_meta_list(attr.into()).unwrap();
-    let global_attr = BitfieldAttr::from_list(&global_attr).unwrap();
     let item = parse_macro_input!(input as ItemStruct);
+    expand_bitfield(attr.into(), item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand_bitfield(
+    attr: proc_macro2::TokenStream,
+    item: ItemStruct,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let metas = NestedMeta::parse_meta_list(attr)?;
+    check_scope(&metas, true)?;
+    let global_attr = BitfieldAttr::from_list(&metas)?.check()?;
+
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.generics,
+            "bitfield structs cannot be generic",
+        ));
+    }
+    let syn::Fields::Named(named) = &item.fields else {
+        return Err(syn::Error::new_spanned(
+            &item.ident,
+            "bitfield structs must have named fields",
+        ));
+    };
+
+    // Parse the attributes of every field up front to report all errors at once
+    let mut errors = darling::Error::accumulator();
+    let mut fields: Vec<(BitfieldAttr, &Field)> = named
+        .named
+        .iter()
+        .filter_map(|f| {
+            errors
+                .handle(BitfieldAttr::from_attributes(&f.attrs))
+                .map(|attr| (attr, f))
+        })
+        .collect();
+    errors.finish()?;
+
+    // The payload is not part of the `FieldBlock`
+    let payload = match fields.iter().position(|(attr, _)| attr.payload) {
+        Some(i) if i + 1 == fields.len() => fields.pop(),
+        Some(i) => {
+            return Err(syn::Error::new_spanned(
+                &fields[i].1.ident,
+                "`payload` must be the last field",
+            ))
+        }
+        None => None,
+    };
 
     let attrs = &item.attrs;
     let vis = &item.vis;
     let name = &item.ident;
+    let name_mut = format_ident!("{}Mut", name);
+    let name_buf = format_ident!("{}Buf", name);
 
     // convert the name to snake case to get the name of module
     let mod_name = format_ident!(
         "{}",
-        name.to_string()
+        name.unraw()
+            .to_string()
             .with_boundaries(&[
                 Boundary::Acronym,
                 Boundary::DigitUpper,
@@ -294,128 +1619,276 @@ pub fn bitfield(attr: TokenStream, input: TokenStream) -> TokenStream {
     );
 
     let mut field_names: Vec<Ident> = Vec::new();
-    let mut field_types: Vec<Ident> = Vec::new();
+    let mut field_types: Vec<proc_macro2::TokenStream> = Vec::new();
     let mut field_defs: Vec<proc_macro2::TokenStream> = Vec::new();
     let mut field_methods: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut accessor_names: Vec<Ident> = Vec::new();
+    // Statements of `Visit::visit` and of the owned buffer's `new`
+    let mut visits: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut resets: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut validations: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut constant_checks: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut field_layouts: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut const_methods: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut builder_fields: Vec<(Ident, bool, Option<syn::Path>)> = Vec::new();
+    // Every field but the reserved ones
+    let mut debug_fields: Vec<(Ident, Shown)> = Vec::new();
 
     let mut hybrid = false;
-    let mut hybrid_field: (Option<Ident>, u8, Vec<(u8, Field)>) = (
+    let mut hybrid_field: (Option<Ident>, u32, Vec<Subfield>) = (
         None,   // name
         0,      // bits
         vec![], // subfields
     );
 
-    for field in &item.fields {
-        let field_attr = BitfieldAttr::from_attributes(&field.attrs).unwrap();
-
-        // let field_ty = &field.ty;
-
+    for (i, (field_attr, field)) in fields.iter().enumerate() {
         if hybrid {
             // Update hybrid field
-            let bit = field_attr.bits.unwrap(); // Assume bits is not None
-            hybrid_field.0 = hybrid_field
-                .0
-                .map(|pre| format_ident!("{}_{}", pre, field.ident.as_ref().unwrap()));
+            let Some(bit) = field_attr.bits else {
+                let (_, _, first) = &hybrid_field.2[0];
+                return Err(syn::Error::new_spanned(
+                    &field.ident,
+                    format!(
+                        "field `{}` needs `bits` to continue the hybrid field started by `{}` ({} bits so far)",
+                        field.ident.as_ref().unwrap(),
+                        first.ident.as_ref().unwrap(),
+                        hybrid_field.1,
+                    ),
+                ));
+            };
+            // Leading underscores of names such as `_rsvd` would break snake case
+            hybrid_field.0 = hybrid_field.0.map(|pre| {
+                let name = field.ident.as_ref().unwrap().unraw().to_string();
+                format_ident!("{}_{}", pre, name.trim_start_matches('_'))
+            });
             hybrid_field.1 += bit;
-            hybrid_field.2.push((bit, field.clone()));
+            hybrid_field.2.push((bit, field_attr, field));
+        } else if field_attr.nested {
+            let (view, buf) = nested_paths(field)?;
+            let field_name = field.ident.clone().unwrap();
+            let (field_method, field_layout) = gen_nested_field(field, &view, &buf);
 
-            // Check if the bits can be composed into 8, 16, 32, 64
-            if hybrid_field.1 % 8 != 0 {
-                // Cannot be composed into 8, 16, 32, 64
-                continue;
-            } else {
-                // Can be composed into 8, 16, 32, 64
-                hybrid = false;
-
-                // Generate the hybrid field
-                // Step 1: Generate Field<SubfieldSpec> for each subfield
-                //         Calculate the mask and shift for each subfield
-                // Step 2: Generate HybridField: union of all subfields
-                // Step 3: Generate methods for accessing the subfields
-
-                let hybrid_field_name = hybrid_field.0.clone();
-
-                // Step 1
-                let mut subfields_names: Vec<Ident> = Vec::new();
-                let mut subfields_types: Vec<Ident> = Vec::new();
-                let mut prefix_bits: u8 = 0;
-                for (b, f) in &hybrid_field.2 {
-                    // Calculate the mask and shift
-                    // | 0; prefix_bits | 1; b | 0; shift |
-                    let shift = hybrid_field.1 - prefix_bits - b;
-                    let mask: u64 = ((1 << b) - 1) << shift;
-                    prefix_bits += b;
-
-                    let (subfield_name, subfield_type, subfield_def, subfield_methods) =
-                        // gen_field_def(f, Some(hybrid_field.1), mask, shift, &hybrid_field_name);
-                        gen_field_def(f, global_attr.clone(), Some(hybrid_field.1), mask, shift,&hybrid_field_name);
-
-                    subfields_names.push(subfield_name);
-                    subfields_types.push(subfield_type);
-                    field_defs.push(subfield_def);
-                    field_methods.push(subfield_methods);
-                }
-
-                // Step 2
-                let hybrid_field_type = format_ident!(
-                    "{}",
-                    hybrid_field
-                        .0
-                        .unwrap()
-                        .to_string()
-                        .with_boundaries(&[
-                            Boundary::Acronym,
-                            Boundary::DigitUpper,
-                            Boundary::LowerUpper,
-                            Boundary::Underscore,
-                        ])
-                        .to_case(convert_case::Case::UpperCamel)
-                );
-                let hybrid_field_def = quote! {
-                    #[repr(C)]
-                    pub union #hybrid_field_type {
-                        #(#subfields_names: core::mem::ManuallyDrop<#subfields_types>,)*
-                    }
-                };
-
-                // Update the vectors
-                field_names.push(hybrid_field_name.unwrap());
-                field_types.push(hybrid_field_type);
-                field_defs.push(hybrid_field_def);
-
-                // Reset the hybrid field
-                hybrid_field.0 = None; // name
-                hybrid_field.1 = 0; // bits
-                hybrid_field.2.clear(); // subfields
-            }
+            accessor_names.push(field_name.clone());
+            visits.push(quote! {
+                visitor.enter_nested(&layout::#field_name::INFO);
+                dmbf::Visit::visit(&self.#field_name(), visitor);
+                visitor.leave_nested(&layout::#field_name::INFO);
+            });
+            resets.push(quote! {
+                dmbf::konst::copy(&mut bytes, layout::#field_name::BYTE_OFFSET, &#buf::new().0);
+            });
+            builder_fields.push((field_name.clone(), field_attr.required, Some(buf.clone())));
+            debug_fields.push((field_name.clone(), Shown::Nested(view, buf.clone())));
+            validations.push(quote! { self.#field_name().validate()?; });
+            constant_checks.push(quote! { self.#field_name().check_constants()?; });
+            field_names.push(field_name);
+            field_types.push(quote! { #buf });
+            field_methods.push(field_method);
+            field_layouts.push(field_layout);
+            continue;
+        } else if let Some(bits) = field_attr.bits.filter(|bits| !bits.is_multiple_of(8)) {
+            // Start a hybrid field
+            hybrid = true;
+            hybrid_field.0 = Some(field.ident.as_ref().unwrap().clone());
+            hybrid_field.1 = bits;
+            hybrid_field.2.push((bits, field_attr, field));
         } else {
             // Not hybrid
-
-            // Check if the bits % 8 != 0
-            // if so, set hybrid flag
-            if let Some(bits) = field_attr.bits {
-                if bits % 8 != 0 {
-                    hybrid = true;
-                    hybrid_field.0 = Some(field.ident.as_ref().unwrap().clone());
-                    hybrid_field.1 = bits;
-                    hybrid_field.2.push((bits, field.clone()));
-                    continue;
-                }
+            if field_attr.pad {
+                return Err(syn::Error::new_spanned(
+                    &field.ident,
+                    "`pad` only applies to subfields of a hybrid field",
+                ));
             }
 
             // Generate single field
-            let (field_name, field_type, field_def, field_method) =
-                // gen_field_def(field, field_attr.bits, !0, 0, &None);
-                gen_field_def(field, global_attr.clone(), field_attr.bits, !0, 0,  &None);
+            let mask = vec![!0; field_attr.bits.unwrap_or(0) as usize / 8];
+            let spec_types: &[&syn::Type] = if field_attr.via_field_spec() {
+                &[&field.ty]
+            } else {
+                &[]
+            };
+            let byte_order =
+                gen_byte_order(field_attr.byte_order, spec_types, global_attr.byte_order);
+            let (field_name, field_type, field_def, field_method, field_layout) = gen_field_def(
+                field,
+                field_attr.clone() + &global_attr,
+                byte_order,
+                field_attr.bits,
+                &mask,
+                0,
+                &None,
+            );
 
+            accessor_names.push(field_name.clone());
+            visits.push(gen_visit(&field_name));
+            resets.push(gen_reset(&field_name));
+            if !field_attr.reserved && field_attr.constant.is_none() {
+                builder_fields.push((field_name.clone(), field_attr.required, None));
+            }
+            if !field_attr.reserved {
+                debug_fields.push((field_name.clone(), Shown::Field(field_type.clone())));
+            }
+            validations.extend(gen_value_check(field, field_attr.must_be.as_ref()));
+            constant_checks.extend(gen_value_check(field, field_attr.constant.as_ref()));
             field_names.push(field_name);
-            field_types.push(field_type);
+            field_types.push(quote! { #field_type });
             field_defs.push(field_def);
             field_methods.push(field_method);
+            field_layouts.push(field_layout);
+            const_methods.push(gen_const_methods(
+                field,
+                &(field_attr.clone() + &global_attr),
+            ));
+            continue;
+        }
+
+        // Close the hybrid field once it fills whole bytes. `pad` on the
+        // subfield, or `auto_pad` when no subfield follows, fills the rest of
+        // the last byte with reserved bits that have no accessor.
+        let continued = fields
+            .get(i + 1)
+            .is_some_and(|(attr, _)| attr.bits.is_some());
+        let padding = match hybrid_field.1 % 8 {
+            0 => 0,
+            rem if field_attr.pad || (global_attr.auto_pad && !continued) => 8 - rem,
+            _ => continue,
+        };
+        let total = hybrid_field.1 + padding;
+        hybrid = false;
+
+        // Generate the hybrid field
+        // Step 1: Generate Field<SubfieldSpec> for each subfield
+        //         Calculate the mask and shift for each subfield
+        // Step 2: Generate HybridField: union of all subfields
+        // Step 3: Generate methods for accessing the subfields
+
+        let hybrid_field_name = hybrid_field.0.clone();
+
+        // The byte order and bit order of the group may be set on any
+        // subfield, otherwise they are taken from the struct
+        let group_attr = gen_group_attr(&hybrid_field.2)?;
+        let typed: Vec<&Field> = hybrid_field
+            .2
+            .iter()
+            .filter(|(_, attr, _)| attr.via_field_spec())
+            .map(|(_, _, f)| *f)
+            .collect();
+        let group_types: Vec<&syn::Type> = typed.iter().map(|f| &f.ty).collect();
+        let byte_order =
+            gen_byte_order(group_attr.byte_order, &group_types, global_attr.byte_order);
+        if group_attr.byte_order.is_none() {
+            field_defs.push(gen_byte_order_check(&typed));
+        }
+        let bit_order = group_attr
+            .bit_order
+            .or(global_attr.bit_order)
+            .unwrap_or_default();
+
+        // Step 1
+        let mut subfields_names: Vec<Ident> = Vec::new();
+        let mut subfields_types: Vec<Ident> = Vec::new();
+        let mut prefix_bits: u32 = 0;
+        for (b, a, f) in &hybrid_field.2 {
+            // Calculate the mask and shift
+            // Msb0: | 0; prefix_bits | 1; b | 0; shift |
+            // Lsb0: | 0; ... | 1; b | 0; prefix_bits = shift |
+            let shift = match bit_order {
+                BitOrderAttr::Msb0 => total - prefix_bits - b,
+                BitOrderAttr::Lsb0 => prefix_bits,
+            };
+            let mask = gen_mask(total, *b, shift);
+            prefix_bits += b;
+
+            let (subfield_name, subfield_type, subfield_def, subfield_methods, subfield_layout) =
+                gen_field_def(
+                    f,
+                    (*a).clone() + &global_attr,
+                    byte_order.clone(),
+                    Some(total),
+                    &mask,
+                    shift,
+                    &hybrid_field_name,
+                );
+
+            accessor_names.push(subfield_name.clone());
+            visits.push(gen_visit(&subfield_name));
+            resets.push(gen_reset(&subfield_name));
+            if !a.reserved && a.constant.is_none() {
+                builder_fields.push((subfield_name.clone(), a.required, None));
+            }
+            if !a.reserved {
+                debug_fields.push((subfield_name.clone(), Shown::Field(subfield_type.clone())));
+            }
+            validations.extend(gen_value_check(f, a.must_be.as_ref()));
+            constant_checks.extend(gen_value_check(f, a.constant.as_ref()));
+            subfields_names.push(subfield_name);
+            subfields_types.push(subfield_type);
+            field_defs.push(subfield_def);
+            field_methods.push(subfield_methods);
+            field_layouts.push(subfield_layout);
+            const_methods.push(gen_const_methods(f, &((*a).clone() + &global_attr)));
         }
+
+        // Step 2
+        let hybrid_field_type = format_ident!(
+            "{}",
+            hybrid_field
+                .0
+                .unwrap()
+                .unraw()
+                .to_string()
+                .with_boundaries(&[
+                    Boundary::Acronym,
+                    Boundary::DigitUpper,
+                    Boundary::LowerUpper,
+                    Boundary::Underscore,
+                ])
+                .to_case(convert_case::Case::UpperCamel)
+        );
+        let hybrid_field_def = quote! {
+            #[repr(C)]
+            pub union #hybrid_field_type {
+                #(#subfields_names: core::mem::ManuallyDrop<#subfields_types>,)*
+            }
+        };
+
+        // Update the vectors
+        field_names.push(hybrid_field_name.unwrap());
+        field_types.push(quote! { #hybrid_field_type });
+        field_defs.push(hybrid_field_def);
+
+        // Reset the hybrid field
+        hybrid_field.0 = None; // name
+        hybrid_field.1 = 0; // bits
+        hybrid_field.2.clear(); // subfields
     }
 
-    quote! {
+    if hybrid {
+        let (_, _, last) = hybrid_field.2.last().unwrap();
+        return Err(syn::Error::new_spanned(
+            &last.ident,
+            format!(
+                "hybrid field `{}` is {} bits short of a whole byte; add subfields or close it with `#[bitfield(pad)]`",
+                hybrid_field.0.unwrap(),
+                8 - hybrid_field.1 % 8,
+            ),
+        ));
+    }
+
+    let (payload_impls, payload_check) = match &payload {
+        Some((attr, field)) => (
+            gen_payload(name, field, attr.len.as_ref(), &accessor_names)?,
+            quote! { view.payload_range()?; },
+        ),
+        None => (quote! {}, quote! {}),
+    };
+    let fmt_impls = gen_fmt_impls(name, &debug_fields, global_attr.display);
+    let serde_impls = gen_serde_impls(name, &name_buf, &debug_fields);
+    let name_builder = format_ident!("{}Builder", name);
+    let builder = gen_builder(&name_builder, &name_buf, &builder_fields);
+
+    Ok(quote! {
         pub mod #mod_name{
             use super::*;
 
@@ -427,54 +1900,285 @@ pub fn bitfield(attr: TokenStream, input: TokenStream) -> TokenStream {
                 #(#field_names: #field_types,)*
             }
 
+            // Every field is stored as bytes, so views can sit at any offset
+            const _: () = assert!(core::mem::align_of::<FieldBlock>() == 1);
+
+            /// Position of every field in the `FieldBlock`
+            pub mod layout {
+                /// Size of the `FieldBlock` in bytes
+                pub const SIZE: usize = core::mem::size_of::<super::FieldBlock>();
+
+                /// Every field, in declaration order
+                pub const FIELDS: &[dmbf::FieldInfo] = &[#(#accessor_names::INFO),*];
+
+                #(#field_layouts)*
+            }
+
             impl FieldBlock {
                 #(#field_methods)*
+
+                /// Check that every constant field holds its value and every
+                /// reserved field its `must_be` value
+                pub fn validate(&self) -> Result<(), dmbf::ValidationError> {
+                    self.check_constants()?;
+                    #(#validations)*
+                    Ok(())
+                }
+
+                // Public for the structs this one is nested in
+                #[doc(hidden)]
+                #[inline]
+                pub fn check_constants(&self) -> Result<(), dmbf::ValidationError> {
+                    #(#constant_checks)*
+                    Ok(())
+                }
             }
 
-            pub struct #name<'a> {
-                pub data: &'a [u8],
+            /// View over a buffer holding a `FieldBlock`
+            ///
+            /// `B` is anything that can lend its bytes, such as `&[u8]`, `[u8; N]`,
+            /// `Vec<u8>` or `Box<[u8]>`, so a view can own its buffer. The `*_mut()`
+            /// accessors are available if `B` lends them mutably as well.
+            pub struct #name<B> {
+                pub data: B,
             }
 
-            impl<'a> core::ops::Deref for #name<'a> {
+            /// View over a mutable byte slice
+            pub type #name_mut<'a> = #name<&'a mut [u8]>;
+
+            impl<B> #name<B> {
+                /// Create a view over `data` without checking it
+                ///
+                /// The accessors panic if `data` is shorter than the `FieldBlock`,
+                /// and constant fields are only checked by `validate`.
+                #[inline]
+                pub fn new_unchecked(data: B) -> Self {
+                    Self { data }
+                }
+
+                /// Unwrap the buffer
+                #[inline]
+                pub fn into_inner(self) -> B {
+                    self.data
+                }
+            }
+
+            impl<B: AsRef<[u8]>> #name<B> {
+                /// Create a view over `data`
+                ///
+                /// Fails if `data` is shorter than the `FieldBlock` or a constant
+                /// field does not hold its value. With a payload of a given `len`,
+                /// `data` has to hold the whole payload as well.
+                #[inline]
+                pub fn new(data: B) -> Result<Self, dmbf::Error> {
+                    dmbf::LengthError::check(layout::SIZE, data.as_ref().len())?;
+                    let view = Self { data };
+                    view.check_constants()?;
+                    #payload_check
+                    Ok(view)
+                }
+
+                /// Copy the viewed bytes into an owned buffer
+                #[inline]
+                pub fn to_buf(&self) -> #name_buf {
+                    let mut buf = #name_buf([0u8; layout::SIZE]);
+                    buf.0.copy_from_slice(&self.data.as_ref()[..layout::SIZE]);
+                    buf
+                }
+            }
+
+            // `B` may lend a different slice on every call, so the length is
+            // checked on every access rather than only in `new`
+            impl<B: AsRef<[u8]>> core::ops::Deref for #name<B> {
                 type Target = FieldBlock;
 
                 fn deref(&self) -> &Self::Target {
-                    unsafe { &*(self.data.as_ptr() as *const Self::Target) }
+                    let data = &self.data.as_ref()[..layout::SIZE];
+                    unsafe { &*(data.as_ptr() as *const Self::Target) }
                 }
             }
 
-            impl<'a> core::ops::DerefMut for #name<'a> {
+            impl<B: AsRef<[u8]> + AsMut<[u8]>> core::ops::DerefMut for #name<B> {
                 fn deref_mut(&mut self) -> &mut Self::Target {
-                    unsafe { &mut *(self.data.as_ptr() as *mut Self::Target) }
+                    let data = &mut self.data.as_mut()[..layout::SIZE];
+                    unsafe { &mut *(data.as_mut_ptr() as *mut Self::Target) }
                 }
             }
 
-            impl<'a, T> From<&'a T> for #name<'a>
-            where
-                T: AsRef<[u8]>,
-            {
-                fn from(data: &'a T) -> Self {
-                    Self {
-                        data: data.as_ref(),
-                    }
+            #payload_impls
+
+            #fmt_impls
+
+            impl dmbf::Visit for FieldBlock {
+                const SIZE: usize = layout::SIZE;
+                const FIELDS: &'static [dmbf::FieldInfo] = layout::FIELDS;
+
+                fn visit<V: dmbf::Visitor + ?Sized>(&self, visitor: &mut V) {
+                    #(#visits)*
                 }
             }
 
-            impl<'a> From<&'a [u8]> for #name<'a> {
-                fn from(data: &'a [u8]) -> Self {
-                    Self { data }
+            impl<B: AsRef<[u8]>> dmbf::Visit for #name<B> {
+                const SIZE: usize = layout::SIZE;
+                const FIELDS: &'static [dmbf::FieldInfo] = layout::FIELDS;
+
+                fn visit<V: dmbf::Visitor + ?Sized>(&self, visitor: &mut V) {
+                    dmbf::Visit::visit(&**self, visitor)
+                }
+            }
+
+            impl dmbf::Visit for #name_buf {
+                const SIZE: usize = layout::SIZE;
+                const FIELDS: &'static [dmbf::FieldInfo] = layout::FIELDS;
+
+                fn visit<V: dmbf::Visitor + ?Sized>(&self, visitor: &mut V) {
+                    dmbf::Visit::visit(&*self.as_view(), visitor)
+                }
+            }
+
+            impl<'a> TryFrom<&'a [u8]> for #name<&'a [u8]> {
+                type Error = dmbf::Error;
+
+                fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+                    Self::new(data)
+                }
+            }
+
+            impl<'a> TryFrom<&'a mut [u8]> for #name_mut<'a> {
+                type Error = dmbf::Error;
+
+                fn try_from(data: &'a mut [u8]) -> Result<Self, Self::Error> {
+                    Self::new(data)
+                }
+            }
+
+            impl<'a> From<#name_mut<'a>> for #name<&'a [u8]> {
+                fn from(view: #name_mut<'a>) -> Self {
+                    Self { data: view.data }
+                }
+            }
+
+            // Only on one buffer, so that `#name::SIZE` needs no annotation;
+            // the other views get them from `dmbf::Visit`
+            impl<'a> #name<&'a [u8]> {
+                /// Size of the `FieldBlock` in bytes
+                pub const SIZE: usize = layout::SIZE;
+
+                /// Every field, in declaration order
+                pub const FIELDS: &'static [dmbf::FieldInfo] = layout::FIELDS;
+
+                /// Start building an owned buffer from the default of every field
+                #[inline]
+                pub fn builder() -> #name_builder {
+                    #name_builder::new()
+                }
+            }
+
+            /// Owned buffer holding exactly one `FieldBlock`
+            ///
+            /// `Default` applies the default value of every field.
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct #name_buf(pub [u8; core::mem::size_of::<FieldBlock>()]);
+
+            impl #name_buf {
+                /// Size of the `FieldBlock` in bytes
+                pub const SIZE: usize = layout::SIZE;
+
+                /// Every field, in declaration order
+                pub const FIELDS: &'static [dmbf::FieldInfo] = layout::FIELDS;
+
+                /// Borrow the buffer as a read-only view
+                #[inline]
+                pub fn as_view(&self) -> #name<&[u8]> {
+                    #name { data: &self.0 }
+                }
+
+                /// Borrow the buffer as a mutable view
+                #[inline]
+                pub fn as_view_mut(&mut self) -> #name_mut<'_> {
+                    #name_mut { data: &mut self.0 }
+                }
+            }
+
+            // The const getters are named after the fields, one of which may be `len`
+            #[allow(clippy::len_without_is_empty)]
+            impl #name_buf {
+                /// Buffer holding the default value of every field
+                ///
+                /// Same as `Default`, but usable in const context.
+                pub const fn new() -> Self {
+                    let mut bytes = [0u8; core::mem::size_of::<FieldBlock>()];
+                    #(#resets)*
+                    Self(bytes)
+                }
+
+                /// Wrap `bytes` without checking them
+                #[inline]
+                pub const fn from_bytes(bytes: [u8; core::mem::size_of::<FieldBlock>()]) -> Self {
+                    Self(bytes)
+                }
+
+                /// Unwrap the bytes
+                #[inline]
+                pub const fn into_bytes(self) -> [u8; core::mem::size_of::<FieldBlock>()] {
+                    self.0
                 }
+
+                #(#const_methods)*
             }
 
-            impl<'a> Into<[u8; core::mem::size_of::<FieldBlock>()]> for #name<'a> {
-                fn into(self) -> [u8; core::mem::size_of::<FieldBlock>()] {
-                    let mut data = [0u8; core::mem::size_of::<FieldBlock>()];
-                    data.copy_from_slice(self.data);
-                    data
+            #builder
+
+            #serde_impls
+
+            impl Default for #name_buf {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            impl From<[u8; core::mem::size_of::<FieldBlock>()]> for #name_buf {
+                fn from(data: [u8; core::mem::size_of::<FieldBlock>()]) -> Self {
+                    Self(data)
+                }
+            }
+
+            impl From<#name_buf> for [u8; core::mem::size_of::<FieldBlock>()] {
+                fn from(buf: #name_buf) -> Self {
+                    buf.0
+                }
+            }
+
+            impl AsRef<[u8]> for #name_buf {
+                fn as_ref(&self) -> &[u8] {
+                    &self.0
+                }
+            }
+
+            impl AsMut<[u8]> for #name_buf {
+                fn as_mut(&mut self) -> &mut [u8] {
+                    &mut self.0
                 }
             }
         }
-        #vis use #mod_name::#name;
-    }
-    .into()
+        #[allow(unused_imports)]
+        #vis use #mod_name::{#name, #name_buf, #name_builder, #name_mut};
+        // Lets `SIZE` and `FIELDS` resolve on views over any buffer
+        #[allow(unused_imports)]
+        use dmbf::Visit as _;
+    })
+}
+
+/// Derive `FieldSpec` for a fieldless enum
+///
+/// The underlay is taken from `#[repr(u8 | u16 | u32 | u64)]` and defaults to
+/// `u8`. A single tuple variant such as `Unknown(u8)` catches every raw value
+/// without a matching discriminant. Without it, `get` panics on unknown values
+/// and `try_get` returns `dmbf::InvalidValue`.
+#[proc_macro_derive(BitfieldEnum)]
+pub fn derive_bitfield_enum(input: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(input as syn::DeriveInput);
+    bitfield_enum::expand(item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
@@ -0,0 +1,21 @@
+//! Type states of the generated builders
+//!
+//! A builder has a type parameter for each `required` field, which starts as
+//! `Unset` and becomes `Set` once the field is set.
+
+/// A `required` field that has not been set yet
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Unset;
+
+/// A `required` field that has been set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Set;
+
+/// Implemented by `Set` only, so that `build()` needs every `required` field
+#[diagnostic::on_unimplemented(
+    message = "a `required` field of the builder has not been set",
+    label = "call the setter of every `required` field before `build()`"
+)]
+pub trait IsSet {}
+
+impl IsSet for Set {}
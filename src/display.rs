@@ -0,0 +1,44 @@
+//! Table rendering for the opt-in `Display` of bitfield structs
+
+use core::fmt;
+
+/// Write `rows` of field name, width in bits and value as a table
+///
+/// ```text
+/// +---------+------+-------+
+/// | Field   | Bits | Value |
+/// +---------+------+-------+
+/// | version |    4 | 4     |
+/// | ihl     |    4 | 5     |
+/// +---------+------+-------+
+/// ```
+pub fn write_table(f: &mut fmt::Formatter<'_>, rows: &[(&str, u32, String)]) -> fmt::Result {
+    let name_width = rows
+        .iter()
+        .map(|r| r.0.len())
+        .fold("Field".len(), usize::max);
+    let value_width = rows
+        .iter()
+        .map(|r| r.2.chars().count())
+        .fold("Value".len(), usize::max);
+
+    let rule = format!(
+        "+-{}-+------+-{}-+",
+        "-".repeat(name_width),
+        "-".repeat(value_width)
+    );
+    writeln!(f, "{rule}")?;
+    writeln!(
+        f,
+        "| {:name_width$} | Bits | {:value_width$} |",
+        "Field", "Value"
+    )?;
+    writeln!(f, "{rule}")?;
+    for (name, bits, value) in rows {
+        writeln!(
+            f,
+            "| {name:name_width$} | {bits:4} | {value:value_width$} |"
+        )?;
+    }
+    write!(f, "{rule}")
+}
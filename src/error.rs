@@ -0,0 +1,134 @@
+use core::fmt;
+
+/// Error returned when a buffer is too short to hold a bitfield struct
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthError {
+    /// Minimum number of bytes required
+    pub expected: usize,
+
+    /// Number of bytes actually provided
+    pub actual: usize,
+}
+
+impl LengthError {
+    /// Check that a buffer of `actual` bytes can hold `expected` bytes
+    #[inline]
+    pub fn check(expected: usize, actual: usize) -> Result<(), Self> {
+        if actual < expected {
+            Err(Self { expected, actual })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for LengthError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "buffer too short: expected at least {} bytes, got {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for LengthError {}
+
+/// Error returned when a value does not fit into the width of a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RangeError {
+    /// Width of the field in bits
+    pub bits: u32,
+
+    /// Whether the field holds a two's-complement signed value
+    pub signed: bool,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "value does not fit into {} {} bits",
+            self.bits,
+            if self.signed { "signed" } else { "unsigned" }
+        )
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+/// Error returned when a raw value has no matching target value
+///
+/// This is produced by `try_get` on fields whose target, such as an enum
+/// deriving `BitfieldEnum`, does not cover every raw value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidValue {
+    /// The raw value read from the field
+    pub raw: u64,
+}
+
+impl fmt::Display for InvalidValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid raw value {:#x}", self.raw)
+    }
+}
+
+impl std::error::Error for InvalidValue {}
+
+/// Error returned when a field does not hold the value its layout requires
+///
+/// This is produced by the checked constructors of a view for `constant`
+/// fields, and by `validate` for those and reserved fields with `must_be`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Name of the offending field
+    pub field: &'static str,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "field `{}` does not hold its required value", self.field)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Error returned by the checked constructors of a view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The buffer is too short
+    Length(LengthError),
+
+    /// A constant field does not hold its value
+    Validation(ValidationError),
+}
+
+impl From<LengthError> for Error {
+    fn from(e: LengthError) -> Self {
+        Self::Length(e)
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(e: ValidationError) -> Self {
+        Self::Validation(e)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Length(e) => e.fmt(f),
+            Self::Validation(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Length(e) => Some(e),
+            Self::Validation(e) => Some(e),
+        }
+    }
+}
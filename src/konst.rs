@@ -0,0 +1,155 @@
+//! Field access usable in const context
+//!
+//! Trait methods cannot be called in a `const fn`, so these helpers only read
+//! the associated consts of a `FieldSpec` and move the value bit by bit. They
+//! back the `const fn` accessors generated on the owned buffer type and work
+//! for every underlay, but are slower than the methods of `Field`.
+
+use core::mem::size_of;
+
+use crate::{byte_order_of, ByteOrder, FieldSpec, RawField};
+
+/// Index and mask of bit `n` of a `len` byte value stored at `offset`
+const fn locate(offset: usize, len: usize, order: ByteOrder, n: u32) -> (usize, u8) {
+    let be = len - 1 - (n / 8) as usize;
+    let index = match order {
+        ByteOrder::Big => be,
+        _ => len - 1 - be,
+    };
+    (offset + index, 1 << (n % 8))
+}
+
+/// Bit `n` of the underlay value `v`
+const fn underlay_bit<U: RawField>(v: &U, n: u32) -> bool {
+    let len = size_of::<U>();
+    assert!(n < (len * 8) as u32);
+    let (index, mask) = locate(0, len, U::MEMORY_ORDER.resolve(), n);
+    // SAFETY: `RawField` is sealed to integers and `[u8; N]`, so all `len`
+    // bytes of `v` are initialized, and `n` is below its width, so `index < len`
+    let byte = unsafe { *(v as *const U as *const u8).add(index) };
+    byte & mask != 0
+}
+
+const fn get_bit<F: FieldSpec>(bytes: &[u8], offset: usize, n: u32) -> bool {
+    let len = size_of::<F::Underlay>();
+    let (index, mask) = locate(offset, len, byte_order_of::<F>(), F::SHIFT + n);
+    bytes[index] & mask != 0
+}
+
+const fn set_bit<F: FieldSpec>(bytes: &mut [u8], offset: usize, n: u32, value: bool) {
+    let len = size_of::<F::Underlay>();
+    let (index, mask) = locate(offset, len, byte_order_of::<F>(), F::SHIFT + n);
+    if value {
+        bytes[index] |= mask;
+    } else {
+        bytes[index] &= !mask;
+    }
+}
+
+/// Read the field stored at `offset` as an unsigned integer
+///
+/// Only the low 128 bits of wider fields are returned.
+pub const fn get<F: FieldSpec>(bytes: &[u8], offset: usize) -> u128 {
+    let mut value = 0;
+    let mut n = 0;
+    while n < F::BITS && n < 128 {
+        if get_bit::<F>(bytes, offset, n) {
+            value |= 1 << n;
+        }
+        n += 1;
+    }
+    value
+}
+
+/// Write `value` to the field stored at `offset`, truncating it to `BITS` bits
+pub const fn set<F: FieldSpec>(bytes: &mut [u8], offset: usize, value: u128) {
+    let mut n = 0;
+    while n < F::BITS {
+        set_bit::<F>(bytes, offset, n, n < 128 && value & (1 << n) != 0);
+        n += 1;
+    }
+}
+
+/// Read the field stored at `offset` as a big-endian byte array
+pub const fn get_bytes<F: FieldSpec, const N: usize>(bytes: &[u8], offset: usize) -> [u8; N] {
+    let mut value = [0; N];
+    let mut n = 0;
+    while n < F::BITS && n < (N * 8) as u32 {
+        if get_bit::<F>(bytes, offset, n) {
+            value[N - 1 - (n / 8) as usize] |= 1 << (n % 8);
+        }
+        n += 1;
+    }
+    value
+}
+
+/// Write the big-endian byte array `value` to the field stored at `offset`
+pub const fn set_bytes<F: FieldSpec, const N: usize>(
+    bytes: &mut [u8],
+    offset: usize,
+    value: [u8; N],
+) {
+    let mut n = 0;
+    while n < F::BITS {
+        let bit = n < (N * 8) as u32 && value[N - 1 - (n / 8) as usize] & (1 << (n % 8)) != 0;
+        set_bit::<F>(bytes, offset, n, bit);
+        n += 1;
+    }
+}
+
+/// Write the default value to the field stored at `offset`
+pub const fn reset<F: FieldSpec>(bytes: &mut [u8], offset: usize) {
+    let mut n = 0;
+    while n < F::BITS {
+        set_bit::<F>(bytes, offset, n, underlay_bit(&F::DEFAULT, n));
+        n += 1;
+    }
+}
+
+/// Copy `src` to `offset`, e.g. the bytes of a nested struct
+pub const fn copy(bytes: &mut [u8], offset: usize, src: &[u8]) {
+    let mut i = 0;
+    while i < src.len() {
+        bytes[offset + i] = src[i];
+        i += 1;
+    }
+}
+
+/// Sign-extend the low `bits` bits of `value`
+pub const fn sign_extend(value: u128, bits: u32) -> i128 {
+    match bits {
+        0 => 0,
+        128.. => value as i128,
+        _ => ((value << (128 - bits)) as i128) >> (128 - bits),
+    }
+}
+
+/// Convert the `default`, `must_be` or `constant` value of a field to `U`
+///
+/// Keeps the low `bits` bits of `value`. Returns `None` if the bits above do
+/// not all repeat the sign bit (if `signed`) or are not all zero.
+pub const fn value<S: RawField, U: RawField>(value: S, bits: u32, signed: bool) -> Option<U> {
+    let width = (size_of::<S>() * 8) as u32;
+    let sign = signed && bits > 0 && bits <= width && underlay_bit(&value, bits - 1);
+    let mut n = bits;
+    while n < width {
+        if underlay_bit(&value, n) != sign {
+            return None;
+        }
+        n += 1;
+    }
+
+    let mut out = U::ZERO;
+    let len = size_of::<U>();
+    let mut n = 0;
+    while n < bits && n < width && n < (len * 8) as u32 {
+        if underlay_bit(&value, n) {
+            let (index, mask) = locate(0, len, U::MEMORY_ORDER.resolve(), n);
+            // SAFETY: `RawField` is sealed to integers and `[u8; N]`, so any
+            // byte of `out` may be written, and `n < len * 8`, so `index < len`
+            unsafe { *(&mut out as *mut U as *mut u8).add(index) |= mask };
+        }
+        n += 1;
+    }
+    Some(out)
+}
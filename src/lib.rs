@@ -1,14 +1,78 @@
-pub use dmbf_impl::bitfield;
+pub use dmbf_impl::{bitfield, BitfieldEnum};
+
+pub mod display;
+
+pub mod error;
+pub use error::{Error, InvalidValue, LengthError, RangeError, ValidationError};
+
+pub mod builder;
+
+pub mod konst;
+
+pub mod net;
+pub use net::{MacAddr, ParseMacAddrError};
 
 pub mod underlay;
 pub use underlay::RawField;
 use underlay::RawFieldOps;
 
-use std::cell::Cell;
+pub mod visit;
+pub use visit::{FieldInfo, RawValue, Visit, Visitor};
 
-pub enum Endianness {
-    Lsb0,
+/// Items used by the code that `bitfield` generates
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "serde")]
+    pub use serde;
+}
+
+/// Byte order used to load and store the underlay of a field
+///
+/// For a hybrid field this is the byte order of the whole group. Byte arrays
+/// are treated as big-endian numbers, so `Little` reverses them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ByteOrder {
+    /// Most significant byte first (network byte order)
+    Big,
+    /// Least significant byte first
+    Little,
+    /// Byte order of the target platform
+    Native,
+}
+
+impl ByteOrder {
+    /// Resolve `Native` to `Big` or `Little` for the target platform
+    pub const fn resolve(self) -> Self {
+        match self {
+            ByteOrder::Native if cfg!(target_endian = "big") => ByteOrder::Big,
+            ByteOrder::Native => ByteOrder::Little,
+            order => order,
+        }
+    }
+}
+
+/// Byte order a `Field` with the spec `F` is stored in
+///
+/// Shared by `Field`, `konst` and the layout metadata so that they never
+/// disagree.
+#[doc(hidden)]
+pub const fn byte_order_of<F: FieldSpec>() -> ByteOrder {
+    match F::BYTE_ORDER {
+        Some(byte_order) => byte_order.resolve(),
+        None => ByteOrder::Little,
+    }
+}
+
+/// Bit numbering inside a hybrid group
+///
+/// This only decides the `SHIFT` of each subfield when the `bitfield` macro
+/// lays out a group, so it does not appear in `FieldSpec`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// The first declared subfield takes the most significant bits
     Msb0,
+    /// The first declared subfield takes the least significant bits
+    Lsb0,
 }
 
 pub trait FieldSpec: Sized {
@@ -31,84 +95,166 @@ pub trait FieldSpec: Sized {
     const MASK: Self::Underlay;
 
     /// Shift of the subfield
-    const SHIFT: u8;
+    const SHIFT: u32;
+
+    /// Width of the subfield in bits
+    const BITS: u32 = (core::mem::size_of::<Self::Underlay>() * 8) as u32;
+
+    /// Whether the target is a two's-complement signed integer
+    ///
+    /// Signed values are sign-extended from bit `BITS - 1` when read.
+    const SIGNED: bool = false;
 
-    /// Endianness of the hybrid field
+    /// Byte order of the underlay
+    ///
+    /// `None` leaves the choice to the struct the field is used in. A `Field`
+    /// whose spec has no byte order is little-endian.
     ///
-    /// This is used to determine how MASK and SHIFT are applied.
-    const ENDIANNESS: Endianness;
+    /// A `byte_order` on the field itself takes precedence over this.
+    const BYTE_ORDER: Option<ByteOrder> = None;
 
     /// Conversion from underlying type to target type
     fn from_underlay(v: Self::Underlay) -> Self::Target;
 
     /// Conversion from target type to underlying type
     fn into_underlay(v: Self::Target) -> Self::Underlay;
+
+    /// Checked conversion from underlying type to target type
+    ///
+    /// Targets that cannot represent every raw value return `InvalidValue`.
+    fn try_from_underlay(v: Self::Underlay) -> Result<Self::Target, InvalidValue> {
+        Ok(Self::from_underlay(v))
+    }
+
+    /// Checked conversion from target type to underlying type
+    ///
+    /// Returns `None` if the value does not fit into `BITS` bits.
+    fn try_into_underlay(v: Self::Target) -> Option<Self::Underlay> {
+        Some(Self::into_underlay(v))
+    }
 }
 
-#[derive(Debug)]
+/// A field stored as plain bytes
+///
+/// The value is loaded from and stored to `bytes` explicitly on every access,
+/// so a `Field` has an alignment of 1 and can sit at any offset in a buffer.
+#[repr(transparent)]
 pub struct Field<F: FieldSpec> {
-    value: Cell<F::Underlay>,
+    bytes: <F::Underlay as RawField>::Bytes,
     _marker: core::marker::PhantomData<F>,
 }
 
 impl<F: FieldSpec> Field<F> {
     #[inline]
-    pub fn as_ptr(&self) -> *mut F::Underlay {
-        self.value.as_ptr()
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_ref()
     }
 
     #[inline]
-    pub fn raw(&self) -> F::Underlay {
-        match F::ENDIANNESS {
-            Endianness::Lsb0 => F::Underlay::from_le(self.value.get())
-                .bitand(F::MASK)
-                .shr(F::SHIFT),
-            Endianness::Msb0 => F::Underlay::from_be(self.value.get())
-                .bitand(F::MASK)
-                .shr(F::SHIFT),
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        self.bytes.as_mut()
+    }
+
+    #[inline]
+    fn load(&self) -> F::Underlay {
+        match Self::byte_order() {
+            ByteOrder::Big => F::Underlay::from_be_bytes(self.bytes),
+            _ => F::Underlay::from_le_bytes(self.bytes),
+        }
+    }
+
+    #[inline]
+    fn store(&mut self, value: F::Underlay) {
+        self.bytes = Self::encode(value);
+    }
+
+    #[inline]
+    fn encode(value: F::Underlay) -> <F::Underlay as RawField>::Bytes {
+        match Self::byte_order() {
+            ByteOrder::Big => value.to_be_bytes(),
+            _ => value.to_le_bytes(),
         }
     }
 
+    #[inline]
+    fn byte_order() -> ByteOrder {
+        byte_order_of::<F>()
+    }
+
+    #[inline]
+    pub fn raw(&self) -> F::Underlay {
+        self.load().bitand(F::MASK).shr(F::SHIFT)
+    }
+
     #[inline]
     pub fn get(&self) -> F::Target {
         F::from_underlay(self.raw())
     }
 
+    #[inline]
+    pub fn try_get(&self) -> Result<F::Target, InvalidValue> {
+        F::try_from_underlay(self.raw())
+    }
+
+    #[inline]
+    fn put(&mut self, raw: F::Underlay) {
+        let value = self
+            .load()
+            .bitand(F::MASK.not())
+            .bitor(raw.shl(F::SHIFT).bitand(F::MASK));
+        self.store(value);
+    }
+
+    /// Set the field, truncating the value to `BITS` bits
     #[inline]
     pub fn set(&mut self, v: F::Target) {
-        let value = self.value.get_mut();
-        match F::ENDIANNESS {
-            Endianness::Lsb0 => {
-                *value = value
-                    .bitand(F::MASK.not())
-                    .bitor(F::into_underlay(v).shl(F::SHIFT))
-                    .to_le();
-            }
-            Endianness::Msb0 => {
-                *value = value
-                    .bitand(F::MASK.not())
-                    .bitor(F::into_underlay(v).shl(F::SHIFT))
-                    .to_be();
-            }
-        }
+        self.put(F::into_underlay(v));
+    }
+
+    /// Set the field, failing if the value does not fit into `BITS` bits
+    #[inline]
+    pub fn try_set(&mut self, v: F::Target) -> Result<(), RangeError> {
+        let raw = F::try_into_underlay(v).ok_or(RangeError {
+            bits: F::BITS,
+            signed: F::SIGNED,
+        })?;
+        self.put(raw);
+        Ok(())
     }
 
     #[inline]
     pub fn reset(&mut self) {
-        let value = self.value.get_mut();
-        match F::ENDIANNESS {
-            Endianness::Lsb0 => {
-                *value = value
-                    .bitand(F::MASK.not())
-                    .bitor(F::DEFAULT.shl(F::SHIFT))
-                    .to_le();
-            }
-            Endianness::Msb0 => {
-                *value = value
-                    .bitand(F::MASK.not())
-                    .bitor(F::DEFAULT.shl(F::SHIFT))
-                    .to_be();
-            }
+        self.put(F::DEFAULT);
+    }
+}
+
+impl<F: FieldSpec<Target = bool>> Field<F> {
+    #[inline]
+    pub fn set_bit(&mut self) {
+        self.set(true)
+    }
+
+    #[inline]
+    pub fn clear_bit(&mut self) {
+        self.set(false)
+    }
+
+    #[inline]
+    pub fn toggle(&mut self) {
+        let v = self.get();
+        self.set(!v)
+    }
+}
+
+/// Prints the decoded value, or the `InvalidValue` if it cannot be decoded
+impl<F: FieldSpec> core::fmt::Debug for Field<F>
+where
+    F::Target: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.try_get() {
+            Ok(v) => v.fmt(f),
+            Err(e) => e.fmt(f),
         }
     }
 }
@@ -116,10 +262,7 @@ impl<F: FieldSpec> Field<F> {
 impl<F: FieldSpec> Default for Field<F> {
     fn default() -> Self {
         Self {
-            value: match F::ENDIANNESS {
-                Endianness::Lsb0 => Cell::new(F::DEFAULT.to_le()),
-                Endianness::Msb0 => Cell::new(F::DEFAULT.to_be()),
-            },
+            bytes: Self::encode(F::DEFAULT.shl(F::SHIFT).bitand(F::MASK)),
             _marker: core::marker::PhantomData,
         }
     }
@@ -133,8 +276,7 @@ macro_rules! impl_field_spec_for_raw_field {
 
                 const DEFAULT: Self::Underlay = 0;
                 const MASK: Self::Underlay = !0;
-                const SHIFT: u8 = 0;
-                const ENDIANNESS: Endianness = Endianness::Lsb0;
+                const SHIFT: u32 = 0;
 
                 type Target = Self;
 
@@ -149,30 +291,160 @@ macro_rules! impl_field_spec_for_raw_field {
             }
         )*
     };
-    ($( $l : literal ), *) => {
+}
+
+impl_field_spec_for_raw_field!(u8, u16, u32, u64, u128);
+
+impl<const N: usize> FieldSpec for [u8; N] {
+    type Underlay = [u8; N];
+
+    const DEFAULT: Self::Underlay = [0; N];
+    const MASK: Self::Underlay = [!0; N];
+    const SHIFT: u32 = 0;
+    // Byte arrays keep the order their bytes have in memory
+    const BYTE_ORDER: Option<ByteOrder> = Some(ByteOrder::Big);
+
+    type Target = Self;
+
+    #[inline]
+    fn from_underlay(v: Self::Underlay) -> Self::Target {
+        v
+    }
+    #[inline]
+    fn into_underlay(v: Self::Target) -> Self::Underlay {
+        v
+    }
+}
+
+macro_rules! impl_field_spec_for_signed {
+    ($( $I : ty => $U : ty ), *) => {
         $(
-            impl FieldSpec for [u8; $l] {
-                type Underlay = [u8; $l];
+            impl FieldSpec for $I {
+                type Underlay = $U;
 
-                const DEFAULT: Self::Underlay = [0; $l];
-                const MASK: Self::Underlay = [!0; $l];
-                const SHIFT: u8 = 0;
-                const ENDIANNESS: Endianness = Endianness::Lsb0;
+                const DEFAULT: Self::Underlay = 0;
+                const MASK: Self::Underlay = !0;
+                const SHIFT: u32 = 0;
+                const SIGNED: bool = true;
 
                 type Target = Self;
 
                 #[inline]
                 fn from_underlay(v: Self::Underlay) -> Self::Target {
-                    v
+                    v as $I
                 }
                 #[inline]
                 fn into_underlay(v: Self::Target) -> Self::Underlay {
-                    v
+                    v as $U
                 }
             }
         )*
+    };
+}
+
+impl_field_spec_for_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128);
+
+impl FieldSpec for bool {
+    type Underlay = u8;
+
+    const DEFAULT: Self::Underlay = 0;
+    const MASK: Self::Underlay = 1;
+    const SHIFT: u32 = 0;
+    const BITS: u32 = 1;
+
+    type Target = Self;
+
+    #[inline]
+    fn from_underlay(v: Self::Underlay) -> Self::Target {
+        v != 0
+    }
+    #[inline]
+    fn into_underlay(v: Self::Target) -> Self::Underlay {
+        v as u8
     }
 }
 
-impl_field_spec_for_raw_field!(u8, u16, u32, u64);
-impl_field_spec_for_raw_field!(3, 5, 6, 7);
+macro_rules! impl_byte_order_integers {
+    ($order : expr; $( $(#[$doc : meta])* $Name : ident => $T : ty, $U : ty, $signed : literal ), *) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct $Name;
+
+            impl FieldSpec for $Name {
+                type Underlay = $U;
+
+                const DEFAULT: Self::Underlay = 0;
+                const MASK: Self::Underlay = !0;
+                const SHIFT: u32 = 0;
+                const SIGNED: bool = $signed;
+                const BYTE_ORDER: Option<ByteOrder> = Some($order);
+
+                type Target = $T;
+
+                #[inline]
+                fn from_underlay(v: Self::Underlay) -> Self::Target {
+                    v as $T
+                }
+                #[inline]
+                fn into_underlay(v: Self::Target) -> Self::Underlay {
+                    v as $U
+                }
+            }
+        )*
+    };
+}
+
+/// Big-endian (network byte order) integers
+///
+/// These fields are big-endian regardless of the struct's `byte_order`, and
+/// make a hybrid group they are part of big-endian as well.
+pub mod be {
+    use super::{ByteOrder, FieldSpec};
+
+    impl_byte_order_integers!(ByteOrder::Big;
+        /// Big-endian `u16`
+        U16 => u16, u16, false,
+        /// Big-endian `u32`
+        U32 => u32, u32, false,
+        /// Big-endian `u64`
+        U64 => u64, u64, false,
+        /// Big-endian `u128`
+        U128 => u128, u128, false,
+        /// Big-endian `i16`
+        I16 => i16, u16, true,
+        /// Big-endian `i32`
+        I32 => i32, u32, true,
+        /// Big-endian `i64`
+        I64 => i64, u64, true,
+        /// Big-endian `i128`
+        I128 => i128, u128, true
+    );
+}
+
+/// Little-endian integers
+///
+/// These fields are little-endian regardless of the struct's `byte_order`, and
+/// make a hybrid group they are part of little-endian as well.
+pub mod le {
+    use super::{ByteOrder, FieldSpec};
+
+    impl_byte_order_integers!(ByteOrder::Little;
+        /// Little-endian `u16`
+        U16 => u16, u16, false,
+        /// Little-endian `u32`
+        U32 => u32, u32, false,
+        /// Little-endian `u64`
+        U64 => u64, u64, false,
+        /// Little-endian `u128`
+        U128 => u128, u128, false,
+        /// Little-endian `i16`
+        I16 => i16, u16, true,
+        /// Little-endian `i32`
+        I32 => i32, u32, true,
+        /// Little-endian `i64`
+        I64 => i64, u64, true,
+        /// Little-endian `i128`
+        I128 => i128, u128, true
+    );
+}
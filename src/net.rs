@@ -0,0 +1,157 @@
+use core::{fmt, str::FromStr};
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{ByteOrder, FieldSpec};
+
+/// MAC (EUI-48) address
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddr(pub [u8; 6]);
+
+impl MacAddr {
+    pub const BROADCAST: Self = Self([0xff; 6]);
+
+    pub const fn new(a: u8, b: u8, c: u8, d: u8, e: u8, f: u8) -> Self {
+        Self([a, b, c, d, e, f])
+    }
+
+    pub const fn octets(&self) -> [u8; 6] {
+        self.0
+    }
+}
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(octets: [u8; 6]) -> Self {
+        Self(octets)
+    }
+}
+
+impl From<MacAddr> for [u8; 6] {
+    fn from(addr: MacAddr) -> Self {
+        addr.0
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(f, "{a:02x}:{b:02x}:{c:02x}:{d:02x}:{e:02x}:{g:02x}")
+    }
+}
+
+/// Error returned when parsing a `MacAddr` fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseMacAddrError;
+
+impl fmt::Display for ParseMacAddrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid MAC address syntax")
+    }
+}
+
+impl std::error::Error for ParseMacAddrError {}
+
+/// Parses six colon-separated hex octets, e.g. `00:11:22:aa:bb:cc`
+impl FromStr for MacAddr {
+    type Err = ParseMacAddrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut octets = [0u8; 6];
+        let mut parts = s.split(':');
+        for octet in &mut octets {
+            let part = parts.next().ok_or(ParseMacAddrError)?;
+            if part.is_empty() || part.len() > 2 {
+                return Err(ParseMacAddrError);
+            }
+            *octet = u8::from_str_radix(part, 16).map_err(|_| ParseMacAddrError)?;
+        }
+        match parts.next() {
+            Some(_) => Err(ParseMacAddrError),
+            None => Ok(Self(octets)),
+        }
+    }
+}
+
+/// Serialized as a string by human-readable formats and as its octets
+/// otherwise, like the `std::net` addresses
+#[cfg(feature = "serde")]
+impl serde::Serialize for MacAddr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            self.0.serialize(serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for MacAddr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+            s.parse().map_err(serde::de::Error::custom)
+        } else {
+            <[u8; 6]>::deserialize(deserializer).map(Self)
+        }
+    }
+}
+
+impl FieldSpec for MacAddr {
+    type Underlay = [u8; 6];
+
+    const DEFAULT: Self::Underlay = [0; 6];
+    const MASK: Self::Underlay = [!0; 6];
+    const SHIFT: u32 = 0;
+    const BYTE_ORDER: Option<ByteOrder> = Some(ByteOrder::Big);
+
+    type Target = Self;
+
+    #[inline]
+    fn from_underlay(v: Self::Underlay) -> Self::Target {
+        Self(v)
+    }
+    #[inline]
+    fn into_underlay(v: Self::Target) -> Self::Underlay {
+        v.0
+    }
+}
+
+impl FieldSpec for Ipv4Addr {
+    type Underlay = u32;
+
+    const DEFAULT: Self::Underlay = 0;
+    const MASK: Self::Underlay = !0;
+    const SHIFT: u32 = 0;
+    const BYTE_ORDER: Option<ByteOrder> = Some(ByteOrder::Big);
+
+    type Target = Self;
+
+    #[inline]
+    fn from_underlay(v: Self::Underlay) -> Self::Target {
+        Ipv4Addr::from(v)
+    }
+    #[inline]
+    fn into_underlay(v: Self::Target) -> Self::Underlay {
+        u32::from(v)
+    }
+}
+
+impl FieldSpec for Ipv6Addr {
+    type Underlay = u128;
+
+    const DEFAULT: Self::Underlay = 0;
+    const MASK: Self::Underlay = !0;
+    const SHIFT: u32 = 0;
+    const BYTE_ORDER: Option<ByteOrder> = Some(ByteOrder::Big);
+
+    type Target = Self;
+
+    #[inline]
+    fn from_underlay(v: Self::Underlay) -> Self::Target {
+        Ipv6Addr::from(v)
+    }
+    #[inline]
+    fn into_underlay(v: Self::Target) -> Self::Underlay {
+        u128::from(v)
+    }
+}
@@ -1,30 +1,180 @@
+use crate::ByteOrder;
+
 /// Raw field type
 ///
 /// The following types are supported:
-/// - u8, u16, u32, u64
-/// - [u8; 3], [u8; 5], [u8; 6], [u8; 7]
-pub trait RawField: Copy + Default + RawFieldOps {}
+/// - u8, u16, u32, u64, u128
+/// - [u8; N] for any N
+///
+/// A byte array is treated as a big-endian number.
+///
+/// A raw field is never stored directly. `Field` keeps its `Bytes` and loads
+/// or stores the value explicitly, so every field has an alignment of 1.
+///
+/// The trait is sealed: views cast a byte slice to their fields, and `konst`
+/// reads and writes the bytes of a value, which is only sound for the types
+/// above.
+pub trait RawField: Copy + PartialEq + RawFieldOps + sealed::Sealed {
+    /// In-memory representation of the raw field, always a `[u8; N]`
+    type Bytes: Copy + core::fmt::Debug + AsRef<[u8]> + AsMut<[u8]> + sealed::ByteArray;
+
+    /// All bits cleared
+    const ZERO: Self;
+
+    /// Byte order of the value in memory
+    ///
+    /// This lets `konst` read the bits of a value without calling trait
+    /// methods, which is not possible in const context.
+    const MEMORY_ORDER: ByteOrder;
+
+    fn from_be_bytes(bytes: Self::Bytes) -> Self;
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+    fn to_be_bytes(&self) -> Self::Bytes;
+    fn to_le_bytes(&self) -> Self::Bytes;
+
+    /// Convert the low `bits` bits into another raw field
+    ///
+    /// The value is zero-extended, or sign-extended from bit `bits - 1` if
+    /// `signed` is set. Bits that do not fit into `V` are dropped.
+    fn resize<V: RawField>(&self, bits: u32, signed: bool) -> V {
+        let src = self.to_be_bytes();
+        let src = src.as_ref();
+        let mut dst = V::ZERO.to_be_bytes();
+        let dst_bytes = dst.as_mut();
+
+        let negative = signed && bits > 0 && bit_be(src, bits - 1);
+        let fill = if negative { !0 } else { 0 };
+
+        for (i, byte) in dst_bytes.iter_mut().rev().enumerate() {
+            let low = (i * 8) as u32;
+            let value = if i < src.len() {
+                src[src.len() - 1 - i]
+            } else {
+                0
+            };
+            *byte = if low >= bits {
+                fill
+            } else if low + 8 > bits {
+                let keep = (1_u8 << (bits - low)) - 1;
+                (value & keep) | (fill & !keep)
+            } else {
+                value
+            };
+        }
+
+        V::from_be_bytes(dst)
+    }
+
+    /// Whether the value can be represented in `bits` bits
+    ///
+    /// For `signed` values every bit from `bits - 1` upwards must be a copy of
+    /// the sign bit, otherwise they must all be zero.
+    fn fits(&self, bits: u32, signed: bool) -> bool {
+        let src = self.to_be_bytes();
+        let src = src.as_ref();
+        let total = (src.len() * 8) as u32;
+        if bits >= total {
+            return true;
+        }
 
-macro_rules! impl_raw_field {
-    ($( $U : ty ), *) => {
+        let (first, sign) = match (signed, bits) {
+            (true, 0) => return false,
+            (true, _) => (bits - 1, bit_be(src, bits - 1)),
+            (false, _) => (bits, false),
+        };
+        (first..total).all(|b| bit_be(src, b) == sign)
+    }
+}
+
+mod sealed {
+    /// Implemented by the types listed on `RawField` only
+    pub trait Sealed {}
+
+    impl Sealed for u8 {}
+    impl Sealed for u16 {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+    impl Sealed for u128 {}
+    impl<const N: usize> Sealed for [u8; N] {}
+
+    /// Implemented by `[u8; N]` only, so that any bytes are a valid `Bytes`
+    pub trait ByteArray {}
+
+    impl<const N: usize> ByteArray for [u8; N] {}
+}
+
+/// Test bit `n` (counted from the least significant bit) of a big-endian value
+#[inline]
+fn bit_be(bytes: &[u8], n: u32) -> bool {
+    let index = bytes.len() - 1 - (n / 8) as usize;
+    bytes[index] & (1 << (n % 8)) != 0
+}
+
+macro_rules! impl_raw_field_ux {
+    ($( $Ux : ty ), *) => {
         $(
-            impl RawField for $U {}
+            impl RawField for $Ux {
+                type Bytes = [u8; core::mem::size_of::<$Ux>()];
+
+                const ZERO: Self = 0;
+                const MEMORY_ORDER: ByteOrder = ByteOrder::Native;
+
+                #[inline]
+                fn from_be_bytes(bytes: Self::Bytes) -> Self {
+                    Self::from_be_bytes(bytes)
+                }
+                #[inline]
+                fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                    Self::from_le_bytes(bytes)
+                }
+                #[inline]
+                fn to_be_bytes(&self) -> Self::Bytes {
+                    Self::to_be_bytes(*self)
+                }
+                #[inline]
+                fn to_le_bytes(&self) -> Self::Bytes {
+                    Self::to_le_bytes(*self)
+                }
+            }
         )*
     };
 }
+impl_raw_field_ux!(u8, u16, u32, u64, u128);
 
-impl_raw_field!(u8, u16, [u8; 3], u32, [u8; 5], [u8; 6], [u8; 7], u64);
+impl<const N: usize> RawField for [u8; N] {
+    type Bytes = Self;
+
+    const ZERO: Self = [0; N];
+    const MEMORY_ORDER: ByteOrder = ByteOrder::Big;
+
+    #[inline]
+    fn from_be_bytes(bytes: Self::Bytes) -> Self {
+        bytes
+    }
+    #[inline]
+    fn from_le_bytes(bytes: Self::Bytes) -> Self {
+        let mut ret = bytes;
+        ret.reverse();
+        ret
+    }
+    #[inline]
+    fn to_be_bytes(&self) -> Self::Bytes {
+        *self
+    }
+    #[inline]
+    fn to_le_bytes(&self) -> Self::Bytes {
+        let mut ret = *self;
+        ret.reverse();
+        ret
+    }
+}
 
 pub trait RawFieldOps {
     fn not(&self) -> Self;
     fn bitand(&self, rhs: Self) -> Self;
     fn bitor(&self, rhs: Self) -> Self;
-    fn shl(&self, rhs: u8) -> Self;
-    fn shr(&self, rhs: u8) -> Self;
-    fn from_be(x: Self) -> Self;
-    fn from_le(x: Self) -> Self;
-    fn to_be(&self) -> Self;
-    fn to_le(&self) -> Self;
+    fn shl(&self, rhs: u32) -> Self;
+    fn shr(&self, rhs: u32) -> Self;
 }
 
 macro_rules! impl_raw_field_ops_ux {
@@ -44,132 +194,87 @@ macro_rules! impl_raw_field_ops_ux {
                     self | rhs
                 }
                 #[inline]
-                fn shl(&self, rhs: u8) -> Self {
+                fn shl(&self, rhs: u32) -> Self {
                     self << rhs
                 }
                 #[inline]
-                fn shr(&self, rhs: u8) -> Self {
+                fn shr(&self, rhs: u32) -> Self {
                     self >> rhs
                 }
-                #[inline]
-                fn from_be(x: Self) -> Self {
-                    Self::from_be(x)
-                }
-                #[inline]
-                fn from_le(x: Self) -> Self {
-                    Self::from_le(x)
-                }
-                #[inline]
-                fn to_be(&self) -> Self {
-                    Self::to_be(*self)
-                }
-                #[inline]
-                fn to_le(&self) -> Self {
-                    Self::to_le(*self)
-                }
             }
         )*
     };
 }
-impl_raw_field_ops_ux!(u8, u16, u32, u64);
+impl_raw_field_ops_ux!(u8, u16, u32, u64, u128);
 
-macro_rules! impl_raw_field_ops_u8s {
-    ($( $l : literal ), *) => {
-        $(
-            impl RawFieldOps for [u8; $l] {
-                #[inline]
-                fn not(&self) -> Self {
-                    let mut ret = [0; $l];
-                    for i in 0..$l {
-                        ret[i] = !self[i];
-                    }
-                    ret
-                }
-                #[inline]
-                fn bitand(&self, rhs: Self) -> Self {
-                    if rhs == [!0; $l] {
-                        return *self;
-                    }
-                    let mut ret = [0; $l];
-                    for i in 0..$l {
-                        ret[i] = self[i] & rhs[i];
-                    }
-                    ret
-                }
-                #[inline]
-                fn bitor(&self, rhs: Self) -> Self {
-                    if rhs == [0; $l] {
-                        return *self;
-                    }
-                    let mut ret = [0; $l];
-                    for i in 0..$l {
-                        ret[i] = self[i] | rhs[i];
-                    }
-                    ret
-                }
-                #[inline]
-                fn shl(&self, rhs: u8) -> Self {
-                    if rhs == 0 {
-                        return *self;
-                    }
-
-                    let mut tmp: [u8; 8] = [0; 8];
-                    for i in 8-$l..8 {
-                        tmp[i] = self[i-(8-$l)];
-                    }
-                    let mut tmp = u64::from_be_bytes(tmp);
-                    tmp <<= rhs;
-                    let tmp = tmp.to_be_bytes();
-                    let ret = tmp[8-$l..8].try_into().unwrap();
-                    ret
-                }
-                #[inline]
-                fn shr(&self, rhs: u8) -> Self {
-
-                    if rhs == 0 {
-                        return *self;
-                    }
-
-                    let mut tmp: [u8; 8] = [0; 8];
-                    for i in 8-$l..8 {
-                        tmp[i] = self[i-(8-$l)];
-                    }
-                    let mut tmp = u64::from_be_bytes(tmp);
-                    tmp >>= rhs;
-                    let tmp = tmp.to_be_bytes();
-                    let ret = tmp[8-$l..8].try_into().unwrap();
-                    ret
+impl<const N: usize> RawFieldOps for [u8; N] {
+    #[inline]
+    fn not(&self) -> Self {
+        self.map(|b| !b)
+    }
+    #[inline]
+    fn bitand(&self, rhs: Self) -> Self {
+        if rhs == [!0; N] {
+            return *self;
+        }
+        let mut ret = [0; N];
+        for i in 0..N {
+            ret[i] = self[i] & rhs[i];
+        }
+        ret
+    }
+    #[inline]
+    fn bitor(&self, rhs: Self) -> Self {
+        if rhs == [0; N] {
+            return *self;
+        }
+        let mut ret = [0; N];
+        for i in 0..N {
+            ret[i] = self[i] | rhs[i];
+        }
+        ret
+    }
+    #[inline]
+    fn shl(&self, rhs: u32) -> Self {
+        if rhs == 0 {
+            return *self;
+        }
 
-                }
-                #[inline]
-                fn from_be(x: Self) -> Self {
-                    x
-                }
-                #[inline]
-                fn from_le(x: Self) -> Self {
-                    let mut ret = [0; $l];
-                    for i in 0..$l {
-                        ret[i] = x[$l - i - 1];
-                    }
-                    ret
-                }
-                #[inline]
-                fn to_be(&self) -> Self {
-                    *self
-                }
-                #[inline]
-                fn to_le(&self) -> Self {
-                    let mut ret = [0; $l];
-                    for i in 0..$l {
-                        ret[$l - i - 1] = self[i];
-                    }
-                    ret
-                }
-            }
-        )*
+        // Shift whole bytes towards the front, then carry the remaining bits
+        let bytes = rhs as usize / 8;
+        let bits = rhs % 8;
+        let mut ret = [0; N];
+        for i in 0..N.saturating_sub(bytes) {
+            let hi = self[i + bytes] << bits;
+            let lo = match (bits, self.get(i + bytes + 1)) {
+                (0, _) | (_, None) => 0,
+                (_, Some(next)) => next >> (8 - bits),
+            };
+            ret[i] = hi | lo;
+        }
+        ret
+    }
+    #[inline]
+    fn shr(&self, rhs: u32) -> Self {
+        if rhs == 0 {
+            return *self;
+        }
+
+        // Shift whole bytes towards the back, then carry the remaining bits
+        let bytes = rhs as usize / 8;
+        let bits = rhs % 8;
+        let mut ret = [0; N];
+        for i in bytes..N {
+            let lo = self[i - bytes] >> bits;
+            let hi = match (bits, (i - bytes).checked_sub(1)) {
+                (0, _) | (_, None) => 0,
+                (_, Some(prev)) => self[prev] << (8 - bits),
+            };
+            ret[i] = hi | lo;
+        }
+        ret
     }
 }
-impl_raw_field_ops_u8s!(3, 5, 6, 7);
 
 #[cfg(test)]
 mod tests {
@@ -192,5 +297,69 @@ mod tests {
         );
         assert_eq!([0x00, 0x80, 0x00].shl(1), [0x01, 0x00, 0x00]);
         assert_eq!([0x01, 0x00, 0x00].shr(1), [0x00, 0x80, 0x00]);
+
+        // test wider arrays against u128
+        let v: u128 = 0x0123_4567_89ab_cdef_fedc_ba98_7654_3210;
+        for rhs in [0, 1, 7, 8, 9, 63, 64, 65, 127] {
+            assert_eq!(v.to_be_bytes().shl(rhs), (v << rhs).to_be_bytes());
+            assert_eq!(v.to_be_bytes().shr(rhs), (v >> rhs).to_be_bytes());
+        }
+        assert_eq!([0xff_u8; 32].shl(255)[0], 0x80);
+        assert_eq!([0xff_u8; 32].shr(255)[31], 0x01);
+        assert_eq!([0xf0_u8; 20].not(), [0x0f; 20]);
+    }
+
+    #[test]
+    fn test_raw_field_bytes() {
+        assert_eq!(RawField::to_be_bytes(&0x0102_u16), [0x01, 0x02]);
+        assert_eq!(RawField::to_le_bytes(&0x0102_u16), [0x02, 0x01]);
+        assert_eq!(<u32 as RawField>::from_be_bytes([1, 2, 3, 4]), 0x01020304);
+        assert_eq!(<u32 as RawField>::from_le_bytes([1, 2, 3, 4]), 0x04030201);
+
+        assert_eq!(
+            RawField::to_be_bytes(&[0x01, 0x02, 0x03]),
+            [0x01, 0x02, 0x03]
+        );
+        assert_eq!(
+            RawField::to_le_bytes(&[0x01, 0x02, 0x03]),
+            [0x03, 0x02, 0x01]
+        );
+        assert_eq!(
+            <[u8; 3] as RawField>::from_le_bytes([0x01, 0x02, 0x03]),
+            [0x03, 0x02, 0x01]
+        );
+    }
+
+    #[test]
+    fn test_raw_field_resize() {
+        // zero extension and truncation
+        assert_eq!(0x0fff_u16.resize::<u32>(12, false), 0x0fff);
+        assert_eq!(0xabcd_u16.resize::<u8>(16, false), 0xcd);
+        assert_eq!(0x1f_u8.resize::<u16>(4, false), 0x0f);
+
+        // sign extension
+        assert_eq!(0x0800_u16.resize::<u16>(12, true), 0xf800);
+        assert_eq!(0x07ff_u16.resize::<u16>(12, true), 0x07ff);
+        assert_eq!(0x10_u8.resize::<u64>(5, true), !0x0f);
+        assert_eq!([0x10, 0x00, 0x00].resize::<u32>(21, true), 0xfff0_0000);
+        assert_eq!(
+            0xfff0_0000_u32.resize::<[u8; 3]>(21, false),
+            [0x10, 0x00, 0x00]
+        );
+        assert_eq!((-1_i128 as u128).resize::<[u8; 17]>(128, true), [0xff; 17]);
+        assert_eq!([0xff_u8; 32].resize::<u128>(256, false), !0);
+    }
+
+    #[test]
+    fn test_raw_field_fits() {
+        assert!(0x0f_u8.fits(4, false));
+        assert!(!0x10_u8.fits(4, false));
+        assert!(0xff_u8.fits(8, false));
+
+        // -2048..=2047 fit in 12 signed bits
+        assert!((-2048_i16 as u16).fits(12, true));
+        assert!(2047_u16.fits(12, true));
+        assert!(!2048_u16.fits(12, true));
+        assert!(!(-2049_i16 as u16).fits(12, true));
     }
 }
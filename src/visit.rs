@@ -0,0 +1,107 @@
+//! Field metadata and a visitor over the fields of a bitfield struct
+//!
+//! Tools such as hex annotators, diffs or table printers can be written once
+//! against `Visit` instead of the accessors of each struct.
+
+use crate::{ByteOrder, Field, FieldSpec, RawField};
+
+/// Position and description of a field
+///
+/// The offsets follow the `layout` module of the struct: a field is loaded
+/// from `byte_len` bytes at `byte_offset` with `byte_order`, and then takes
+/// `bit_width` bits starting `bit_offset` bits above the least significant
+/// bit. Subfields of a hybrid field share the storage of their group.
+///
+/// A nested struct is stored as whole big-endian bytes and lists its own
+/// fields in `fields`, with offsets relative to its `byte_offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    /// Name of the field
+    pub name: &'static str,
+
+    /// Offset of the field's storage in bytes
+    pub byte_offset: usize,
+
+    /// Size of the field's storage in bytes
+    pub byte_len: usize,
+
+    /// Shift of the field inside its storage
+    pub bit_offset: u32,
+
+    /// Width of the field in bits
+    pub bit_width: u32,
+
+    /// Byte order of the storage, resolved for the target platform
+    pub byte_order: ByteOrder,
+
+    /// Whether the field is `reserved`
+    pub reserved: bool,
+
+    /// Doc comment of the field, without the leading space of each line
+    pub doc: &'static str,
+
+    /// Fields of a nested struct, empty for any other field
+    pub fields: &'static [FieldInfo],
+}
+
+/// Raw bits of a field, before they are converted to its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RawValue<'a> {
+    /// A field of at most 64 bits, zero-extended
+    Int(u64),
+    /// A wider field as a big-endian number of the storage's size
+    Bytes(&'a [u8]),
+}
+
+/// Receiver of the fields of a `Visit`
+///
+/// The fields of a nested struct are visited between `enter_nested` and
+/// `leave_nested`. Closures taking `(&FieldInfo, RawValue)` are visitors as
+/// well and see the nested fields like any other.
+pub trait Visitor {
+    fn visit_field(&mut self, info: &FieldInfo, value: RawValue<'_>);
+
+    /// Called before the fields of the nested struct described by `info`
+    fn enter_nested(&mut self, info: &FieldInfo) {
+        let _ = info;
+    }
+
+    /// Called after the fields of the nested struct described by `info`
+    fn leave_nested(&mut self, info: &FieldInfo) {
+        let _ = info;
+    }
+}
+
+impl<F: FnMut(&FieldInfo, RawValue<'_>)> Visitor for F {
+    fn visit_field(&mut self, info: &FieldInfo, value: RawValue<'_>) {
+        self(info, value)
+    }
+}
+
+/// A bitfield struct whose fields can be listed and visited
+///
+/// `#[bitfield]` implements this for the views, the owned buffer and the
+/// `FieldBlock`. Reserved fields are included and marked in `FieldInfo`.
+pub trait Visit {
+    /// Size of the `FieldBlock` in bytes
+    const SIZE: usize;
+
+    /// Every field, in declaration order
+    const FIELDS: &'static [FieldInfo];
+
+    /// Call `visitor` with the raw value of every field, in declaration order
+    fn visit<V: Visitor + ?Sized>(&self, visitor: &mut V);
+}
+
+impl<F: FieldSpec> Field<F> {
+    /// Pass the raw value of the field to `visitor`
+    #[inline]
+    pub fn visit<V: Visitor + ?Sized>(&self, info: &FieldInfo, visitor: &mut V) {
+        let raw = self.raw();
+        if F::BITS <= 64 {
+            visitor.visit_field(info, RawValue::Int(raw.resize(F::BITS, false)));
+        } else {
+            visitor.visit_field(info, RawValue::Bytes(raw.to_be_bytes().as_ref()));
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use dmbf::{bitfield, MacAddr};
+
+#[bitfield(byte_order = "little")]
+struct Addrs {
+    mac: MacAddr,
+    v4: Ipv4Addr,
+    v6: Ipv6Addr,
+    tail: u16,
+}
+
+#[test]
+fn address_fields() {
+    let mut buf = AddrsBuf::default();
+    let mut addrs = buf.as_view_mut();
+    assert_eq!(addrs.mac().get(), MacAddr::default());
+    assert_eq!(addrs.v4().get(), Ipv4Addr::UNSPECIFIED);
+    assert_eq!(addrs.v6().get(), Ipv6Addr::UNSPECIFIED);
+
+    addrs
+        .mac_mut()
+        .set(MacAddr::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55));
+    addrs.v4_mut().set(Ipv4Addr::new(192, 168, 0, 1));
+    addrs.v6_mut().set(Ipv6Addr::LOCALHOST);
+    addrs.tail_mut().set(0x1234);
+
+    // Addresses stay in network byte order in a little-endian struct
+    assert_eq!(&buf.0[0..6], &[0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+    assert_eq!(&buf.0[6..10], &[192, 168, 0, 1]);
+    assert_eq!(&buf.0[10..26], &Ipv6Addr::LOCALHOST.octets());
+    assert_eq!(&buf.0[26..28], &[0x34, 0x12]);
+
+    let addrs = buf.as_view();
+    assert_eq!(addrs.mac().get().to_string(), "00:11:22:33:44:55");
+    assert_eq!(addrs.v4().get(), Ipv4Addr::new(192, 168, 0, 1));
+    assert_eq!(addrs.v6().get(), Ipv6Addr::LOCALHOST);
+}
+
+#[test]
+fn parse_mac_addr() {
+    let mac: MacAddr = "00:11:22:aa:BB:cc".parse().unwrap();
+    assert_eq!(mac, MacAddr::new(0x00, 0x11, 0x22, 0xaa, 0xbb, 0xcc));
+    assert_eq!(mac.to_string().parse(), Ok(mac));
+
+    assert!("00:11:22:aa:bb".parse::<MacAddr>().is_err());
+    assert!("00:11:22:aa:bb:cc:dd".parse::<MacAddr>().is_err());
+    assert!("00:11:22:aa:bb:ccc".parse::<MacAddr>().is_err());
+    assert!("00:11:22:aa::cc".parse::<MacAddr>().is_err());
+}
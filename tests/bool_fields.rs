@@ -0,0 +1,53 @@
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "big")]
+struct Flags {
+    #[bitfield(bits = 1)]
+    reserved: bool,
+    #[bitfield(bits = 1)]
+    df: bool,
+    #[bitfield(bits = 1)]
+    mf: bool,
+    #[bitfield(bits = 13)]
+    fragment_offset: u16,
+    #[bitfield(bits = 7)]
+    code: u8,
+    #[bitfield(bits = 1)]
+    last: bool,
+    whole: bool,
+}
+
+#[test]
+fn bool_fields_in_hybrid_group() {
+    let data: [u8; 4] = [0x40, 0x00, 0x01, 0x01];
+    let flags = Flags::new(&data).unwrap();
+
+    assert!(!flags.reserved().get());
+    assert!(flags.df().get());
+    assert!(!flags.mf().get());
+    assert_eq!(flags.fragment_offset().get(), 0);
+    assert_eq!(flags.code().get(), 0);
+    assert!(flags.last().get());
+    assert!(flags.whole().get());
+}
+
+#[test]
+fn bool_helpers() {
+    let mut data: [u8; 4] = [0x00, 0x00, 0x00, 0x00];
+    let mut flags = FlagsMut::new(&mut data).unwrap();
+
+    flags.mf_mut().set_bit();
+    flags.reserved_mut().set_bit();
+    flags.reserved_mut().clear_bit();
+    flags.last_mut().toggle();
+    flags.whole_mut().set(true);
+    assert_eq!(data, [0x20, 0x00, 0x01, 0x01]);
+
+    let mut flags = FlagsMut::new(&mut data).unwrap();
+    flags.fragment_offset_mut().set(0x1fff);
+    flags.last_mut().toggle();
+    assert!(flags.mf().get());
+    assert!(!flags.df().get());
+    assert!(!flags.last().get());
+    assert_eq!(data, [0x3f, 0xff, 0x00, 0x01]);
+}
@@ -0,0 +1,85 @@
+use std::net::Ipv4Addr;
+
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "big")]
+struct Ipv4 {
+    #[bitfield(bits = 4, constant = 4)]
+    version: u8,
+    #[bitfield(bits = 4, default = 5)]
+    ihl: u8,
+    #[bitfield(bits = 6)]
+    dscp: u8,
+    #[bitfield(bits = 2)]
+    ecn: u8,
+    total_length: u16,
+    identification: u16,
+    #[bitfield(bits = 3)]
+    flags: u8,
+    #[bitfield(bits = 13)]
+    fragment_offset: u16,
+    #[bitfield(default = 64)]
+    ttl: u8,
+    #[bitfield(required)]
+    protocol: u8,
+    checksum: u16,
+    #[bitfield(required)]
+    src: Ipv4Addr,
+    #[bitfield(required)]
+    dst: Ipv4Addr,
+}
+
+#[test]
+fn builder() {
+    let buf = Ipv4::builder()
+        .total_length(0x28)
+        .flags(0b010)
+        .protocol(17)
+        .src(Ipv4Addr::new(192, 168, 0, 1))
+        .dst(Ipv4Addr::new(192, 168, 0, 199))
+        .checksum(0xb80e)
+        .build();
+
+    let data: [u8; 20] = buf.into();
+    assert_eq!(
+        data,
+        [
+            0x45, 0x00, 0x00, 0x28, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0xb8, 0x0e, 0xc0, 0xa8,
+            0x00, 0x01, 0xc0, 0xa8, 0x00, 0xc7,
+        ]
+    );
+    assert_eq!(Ipv4::new(&data).unwrap().ttl().get(), 64);
+
+    // Setters may be called in any order and more than once
+    let buf = Ipv4Buf::builder()
+        .dst(Ipv4Addr::LOCALHOST)
+        .ttl(1)
+        .src(Ipv4Addr::LOCALHOST)
+        .protocol(6)
+        .ttl(2)
+        .build();
+    assert_eq!(buf.as_view().ttl().get(), 2);
+    assert_eq!(buf.as_view().protocol().get(), 6);
+}
+
+#[bitfield]
+struct Flags {
+    #[bitfield(bits = 3)]
+    a: u8,
+    #[bitfield(bits = 5)]
+    b: u8,
+}
+
+#[test]
+fn builder_without_required_fields() {
+    let buf = FlagsBuilder::default().a(0x5).build();
+    assert_eq!(buf.0, [0xa0]);
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "field `a`"))]
+fn builder_checks_range_in_debug() {
+    let buf = Flags::builder().a(0x9).b(0x1f).build();
+    // Release builds truncate like `set`
+    assert_eq!(buf.0, [0x3f]);
+}
@@ -0,0 +1,108 @@
+use dmbf::bitfield;
+
+// A 16-bit group holding `a: 4 bits`, `b: 12 bits`, stored as the bytes
+// [0x12, 0x34].
+//
+// - big-endian load:    0x1234
+// - little-endian load: 0x3412
+// - msb0: `a` takes bits 15..12, `b` bits 11..0
+// - lsb0: `a` takes bits 3..0, `b` bits 15..4
+
+#[bitfield(byte_order = "big", bit_order = "msb0")]
+struct BigMsb0 {
+    #[bitfield(bits = 4)]
+    a: u8,
+    #[bitfield(bits = 12)]
+    b: u16,
+}
+
+#[bitfield(byte_order = "big", bit_order = "lsb0")]
+struct BigLsb0 {
+    #[bitfield(bits = 4)]
+    a: u8,
+    #[bitfield(bits = 12)]
+    b: u16,
+}
+
+#[bitfield(byte_order = "little", bit_order = "msb0")]
+struct LittleMsb0 {
+    #[bitfield(bits = 4)]
+    a: u8,
+    #[bitfield(bits = 12)]
+    b: u16,
+}
+
+#[bitfield(byte_order = "little", bit_order = "lsb0")]
+struct LittleLsb0 {
+    #[bitfield(bits = 4)]
+    a: u8,
+    #[bitfield(bits = 12)]
+    b: u16,
+}
+
+const DATA: [u8; 2] = [0x12, 0x34];
+
+#[test]
+fn big_endian_groups() {
+    let v = BigMsb0::new(&DATA).unwrap();
+    assert_eq!(v.a().get(), 0x1);
+    assert_eq!(v.b().get(), 0x234);
+
+    let v = BigLsb0::new(&DATA).unwrap();
+    assert_eq!(v.a().get(), 0x4);
+    assert_eq!(v.b().get(), 0x123);
+}
+
+#[test]
+fn little_endian_groups() {
+    let v = LittleMsb0::new(&DATA).unwrap();
+    assert_eq!(v.a().get(), 0x3);
+    assert_eq!(v.b().get(), 0x412);
+
+    // Matches a C bitfield `struct { uint16_t a : 4; uint16_t b : 12; }` on a
+    // little-endian target
+    let v = LittleLsb0::new(&DATA).unwrap();
+    assert_eq!(v.a().get(), 0x2);
+    assert_eq!(v.b().get(), 0x341);
+}
+
+#[test]
+fn write_back() {
+    let mut data = [0_u8; 2];
+    let mut v = LittleLsb0Mut::new(&mut data).unwrap();
+    v.a_mut().set(0x2);
+    v.b_mut().set(0x341);
+    assert_eq!(data, DATA);
+
+    let mut data = [0_u8; 2];
+    let mut v = BigLsb0Mut::new(&mut data).unwrap();
+    v.a_mut().set(0x4);
+    v.b_mut().set(0x123);
+    assert_eq!(data, DATA);
+}
+
+#[bitfield(byte_order = "big")]
+struct Mixed {
+    be: u16,
+    #[bitfield(byte_order = "little")]
+    le: u16,
+    #[bitfield(byte_order = "native")]
+    ne: u32,
+    // The group order can be set on any subfield
+    #[bitfield(bits = 4)]
+    lo: u8,
+    #[bitfield(bits = 12, byte_order = "little", bit_order = "lsb0")]
+    hi: u16,
+}
+
+#[test]
+fn field_level_byte_order() {
+    let data: [u8; 10] = [0x12, 0x34, 0x12, 0x34, 0x01, 0x02, 0x03, 0x04, 0x12, 0x34];
+    let v = Mixed::new(&data).unwrap();
+
+    assert_eq!(v.be().get(), 0x1234);
+    assert_eq!(v.le().get(), 0x3412);
+    assert_eq!(v.ne().get(), u32::from_ne_bytes([0x01, 0x02, 0x03, 0x04]));
+    assert_eq!(v.lo().get(), 0x2);
+    assert_eq!(v.hi().get(), 0x341);
+}
@@ -0,0 +1,79 @@
+use dmbf::{be, bitfield, le};
+
+#[bitfield]
+struct Header {
+    length: be::U16,
+    offset: le::U32,
+    delta: be::I16,
+    #[bitfield(bits = 24)]
+    short: be::U32,
+    #[bitfield(byte_order = "little")]
+    overridden: be::U16,
+    plain: u16,
+}
+
+#[bitfield(byte_order = "little")]
+struct Flags {
+    #[bitfield(bits = 1)]
+    reserved: bool,
+    #[bitfield(bits = 1)]
+    df: bool,
+    #[bitfield(bits = 1)]
+    mf: bool,
+    #[bitfield(bits = 13)]
+    fragment_offset: be::U16,
+    tail: u16,
+}
+
+#[test]
+fn byte_order_types() {
+    let data: [u8; 15] = [
+        0x01, 0x02, // length
+        0x01, 0x02, 0x03, 0x04, // offset
+        0xff, 0xfe, // delta
+        0x01, 0x02, 0x03, // short
+        0x01, 0x02, // overridden
+        0x01, 0x02, // plain
+    ];
+    let header = Header::new(&data).unwrap();
+
+    assert_eq!(header.length().get(), 0x0102);
+    assert_eq!(header.offset().get(), 0x04030201);
+    assert_eq!(header.delta().get(), -2);
+    assert_eq!(header.short().get(), 0x010203);
+    assert_eq!(header.overridden().get(), 0x0201);
+    assert_eq!(header.plain().get(), 0x0201);
+}
+
+#[test]
+fn byte_order_types_set() {
+    let mut data = [0_u8; 15];
+    let mut header = HeaderMut::new(&mut data).unwrap();
+
+    header.length_mut().set(0x0102);
+    header.offset_mut().set(0x01020304);
+    header.short_mut().set(0x0a0b0c);
+    assert!(header.short_mut().try_set(0x0100_0000).is_err());
+
+    assert_eq!(data[..11], [1, 2, 4, 3, 2, 1, 0, 0, 0x0a, 0x0b, 0x0c]);
+}
+
+#[test]
+fn byte_order_type_in_hybrid_group() {
+    // The be::U16 member makes the whole group big-endian, while `tail`
+    // still follows the struct
+    let data: [u8; 4] = [0x40, 0x10, 0x01, 0x02];
+    let flags = Flags::new(&data).unwrap();
+
+    assert!(!flags.reserved().get());
+    assert!(flags.df().get());
+    assert!(!flags.mf().get());
+    assert_eq!(flags.fragment_offset().get(), 0x0010);
+    assert_eq!(flags.tail().get(), 0x0201);
+
+    let mut data = [0_u8; 4];
+    let mut flags = FlagsMut::new(&mut data).unwrap();
+    flags.mf_mut().set_bit();
+    flags.fragment_offset_mut().set(0x1234);
+    assert_eq!(data, [0x32, 0x34, 0, 0]);
+}
@@ -0,0 +1,134 @@
+use dmbf::{be, bitfield, le};
+
+#[bitfield(byte_order = "big")]
+struct Arp {
+    #[bitfield(default = 1)]
+    htype: u16,
+    #[bitfield(default = 0x0800)]
+    ptype: u16,
+    #[bitfield(constant = 6)]
+    hlen: u8,
+    #[bitfield(constant = 4)]
+    plen: u8,
+    oper: u16,
+    sha: [u8; 6],
+    spa: [u8; 4],
+    tha: [u8; 6],
+    tpa: [u8; 4],
+}
+
+const ARP_REQUEST: [u8; 28] = ArpBuf::new()
+    .with_oper(1)
+    .with_sha([0x00, 0x11, 0x22, 0x33, 0x44, 0x55])
+    .with_spa([192, 168, 0, 1])
+    .with_tpa([192, 168, 0, 2])
+    .into_bytes();
+
+const _: () = assert!(ArpBuf::from_bytes(ARP_REQUEST).oper() == 1);
+const _: () = assert!(ArpBuf::from_bytes(ARP_REQUEST).hlen() == 6);
+
+#[test]
+fn const_arp_request() {
+    assert_eq!(
+        ARP_REQUEST,
+        [
+            0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01, 0x00, 0x11, 0x22, 0x33, 0x44, 0x55,
+            192, 168, 0, 1, 0, 0, 0, 0, 0, 0, 192, 168, 0, 2,
+        ]
+    );
+
+    // The runtime accessors see the same values
+    let buf = ArpBuf::from(ARP_REQUEST);
+    let arp = buf.as_view();
+    assert_eq!(arp.htype().get(), 1);
+    assert_eq!(arp.ptype().get(), 0x0800);
+    assert_eq!(arp.oper().get(), 1);
+    assert_eq!(arp.spa().get(), [192, 168, 0, 1]);
+    assert_eq!(Arp::new(&ARP_REQUEST).err(), None);
+}
+
+#[bitfield(byte_order = "little")]
+struct Mixed {
+    #[bitfield(bits = 3)]
+    a: u8,
+    #[bitfield(bits = 5)]
+    b: i8,
+    #[bitfield(bits = 1)]
+    c: bool,
+    #[bitfield(bits = 15, default = 0x1234)]
+    d: be::U16,
+    e: le::I32,
+    #[bitfield(bits = 3, pad)]
+    f: u8,
+    g: [u8; 3],
+}
+
+const MIXED: MixedBuf = MixedBuf::new()
+    .with_a(0x5)
+    .with_b(-3)
+    .with_c(true)
+    .with_e(-2)
+    .with_f(0xff)
+    .with_g([1, 2, 3]);
+
+#[test]
+fn const_matches_runtime() {
+    const _: () = assert!(MIXED.b() == -3);
+    assert_eq!(MIXED.a(), 0x5);
+    assert!(MIXED.c());
+    assert_eq!(MIXED.d(), 0x1234);
+    assert_eq!(MIXED.e(), -2);
+    assert_eq!(MIXED.f(), 0x7);
+    assert_eq!(MIXED.g(), [1, 2, 3]);
+
+    let mut buf = MixedBuf::default();
+    let mut view = buf.as_view_mut();
+    view.a_mut().set(0x5);
+    view.b_mut().set(-3);
+    view.c_mut().set(true);
+    view.e_mut().set(-2);
+    view.f_mut().set(0xff);
+    view.g_mut().set([1, 2, 3]);
+    assert_eq!(buf, MIXED);
+}
+
+mod proto {
+    pub mod le {
+        use dmbf::FieldSpec;
+
+        /// Not `dmbf::le::U16`, despite the name
+        pub struct U16;
+
+        impl FieldSpec for U16 {
+            type Underlay = [u8; 2];
+            type Target = [u8; 2];
+
+            const DEFAULT: Self::Underlay = [0; 2];
+            const MASK: Self::Underlay = [!0; 2];
+            const SHIFT: u32 = 0;
+
+            fn from_underlay(v: Self::Underlay) -> Self::Target {
+                v
+            }
+
+            fn into_underlay(v: Self::Target) -> Self::Underlay {
+                v
+            }
+        }
+    }
+}
+
+#[bitfield(byte_order = "big")]
+struct Record {
+    len: u16,
+    #[bitfield(default = [0xab, 0xcd])]
+    tag: proto::le::U16,
+}
+
+#[test]
+fn const_targets_by_path() {
+    const RECORD: RecordBuf = RecordBuf::new().with_len(3);
+    assert_eq!(RECORD.len(), 3);
+    assert_eq!(RECORD.into_bytes(), [0x00, 0x03, 0xab, 0xcd]);
+    assert_eq!(RECORD.as_view().tag().get(), [0xab, 0xcd]);
+}
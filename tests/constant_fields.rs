@@ -0,0 +1,105 @@
+use dmbf::{bitfield, ValidationError};
+
+#[bitfield(byte_order = "big")]
+struct Header {
+    #[bitfield(constant = [0x7f, b'E', b'L', b'F'])]
+    magic: [u8; 4],
+    #[bitfield(bits = 4, constant = 4)]
+    version: u8,
+    #[bitfield(bits = 4)]
+    ihl: u8,
+    length: u16,
+}
+
+#[test]
+fn constant_fields() {
+    let buf = HeaderBuf::default();
+    assert_eq!(buf.0, [0x7f, b'E', b'L', b'F', 0x40, 0x00, 0x00]);
+
+    let header = buf.as_view();
+    assert_eq!(header.magic().get(), *b"\x7fELF");
+    assert_eq!(header.version().get(), 4);
+    assert_eq!(header.validate(), Ok(()));
+
+    let mut data = buf.0;
+    let mut header = HeaderMut::new(&mut data).unwrap();
+    header.ihl_mut().set(5);
+    header.length_mut().set(0x1234);
+    assert_eq!(data, [0x7f, b'E', b'L', b'F', 0x45, 0x12, 0x34]);
+}
+
+#[test]
+fn constant_fields_are_checked() {
+    let mut data = [0x7f, b'E', b'L', b'F', 0x65, 0x00, 0x00];
+    assert_eq!(
+        Header::new(&data).err(),
+        Some(dmbf::Error::Validation(ValidationError {
+            field: "version"
+        }))
+    );
+    assert_eq!(
+        Header::new(&data).err().unwrap().to_string(),
+        "field `version` does not hold its required value"
+    );
+
+    data[4] = 0x45;
+    data[0] = 0x7e;
+    assert!(matches!(
+        HeaderMut::try_from(&mut data[..]),
+        Err(dmbf::Error::Validation(ValidationError { field: "magic" }))
+    ));
+
+    // The unchecked constructor leaves the check to `validate`
+    let header = Header::new_unchecked(&data);
+    assert_eq!(header.validate(), Err(ValidationError { field: "magic" }));
+}
+
+#[bitfield]
+struct Elf {
+    #[bitfield(constant = [0x7f, b'E', b'L', b'F'])]
+    magic: [u8; 4],
+    class: u8,
+}
+
+#[test]
+fn constant_byte_arrays_keep_memory_order() {
+    assert!(Elf::new(b"\x7fELF\x02").is_ok());
+    assert!(Elf::new(b"\x7fELG\x02").is_err());
+    assert!(Elf::new(b"FLE\x7f\x02").is_err());
+    assert_eq!(ElfBuf::default().0, [0x7f, b'E', b'L', b'F', 0]);
+}
+
+#[bitfield(byte_order = "big")]
+struct Tagged {
+    #[bitfield(bits = 24, constant = 0x123456)]
+    tag: u32,
+    #[bitfield(bits = 4, constant = 0xa)]
+    kind: u8,
+    #[bitfield(bits = 20)]
+    value: u32,
+}
+
+#[test]
+fn constant_fields_wider_than_a_byte() {
+    let buf = TaggedBuf::default();
+    assert_eq!(buf.0, [0x12, 0x34, 0x56, 0xa0, 0x00, 0x00]);
+
+    let tagged = buf.as_view();
+    assert_eq!(tagged.tag().get(), 0x123456);
+    assert_eq!(tagged.kind().get(), 0xa);
+    assert_eq!(tagged.validate(), Ok(()));
+
+    let mut data = buf.0;
+    data[2] = 0x57;
+    assert_eq!(
+        Tagged::new(&data).err(),
+        Some(dmbf::Error::Validation(ValidationError { field: "tag" }))
+    );
+
+    data[2] = 0x56;
+    data[3] = 0xb1;
+    assert_eq!(
+        Tagged::new(&data).err(),
+        Some(dmbf::Error::Validation(ValidationError { field: "kind" }))
+    );
+}
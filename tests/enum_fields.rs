@@ -0,0 +1,86 @@
+use dmbf::{bitfield, BitfieldEnum, FieldSpec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BitfieldEnum)]
+pub enum Mode {
+    Off,
+    Low,
+    High = 4,
+    Max,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BitfieldEnum)]
+#[repr(u16)]
+pub enum EtherType {
+    Ipv4 = 0x0800,
+    Arp = 0x0806,
+    Ipv6 = 0x86dd,
+    Unknown(u16),
+}
+
+#[bitfield(byte_order = "big")]
+struct Frame {
+    #[bitfield(bits = 4)]
+    mode: Mode,
+    #[bitfield(bits = 4)]
+    other: Mode,
+    ty: EtherType,
+}
+
+#[test]
+fn known_values() {
+    let data: [u8; 3] = [0x45, 0x86, 0xdd];
+    let frame = Frame::new(&data).unwrap();
+
+    assert_eq!(frame.mode().get(), Mode::High);
+    assert_eq!(frame.other().get(), Mode::Max);
+    assert_eq!(frame.other().try_get(), Ok(Mode::Max));
+    assert_eq!(frame.ty().get(), EtherType::Ipv6);
+}
+
+#[test]
+fn unknown_values() {
+    let data: [u8; 3] = [0x27, 0x12, 0x34];
+    let frame = Frame::new(&data).unwrap();
+
+    assert_eq!(frame.mode().try_get(), Err(dmbf::InvalidValue { raw: 2 }));
+    assert_eq!(frame.other().try_get(), Err(dmbf::InvalidValue { raw: 7 }));
+    assert_eq!(frame.ty().get(), EtherType::Unknown(0x1234));
+    assert_eq!(frame.ty().try_get(), Ok(EtherType::Unknown(0x1234)));
+}
+
+#[test]
+#[should_panic(expected = "invalid raw value 0x2")]
+fn unknown_value_panics_on_get() {
+    let data: [u8; 3] = [0x20, 0x08, 0x00];
+    let frame = Frame::new(&data).unwrap();
+
+    frame.mode().get();
+}
+
+#[test]
+fn set_enum_values() {
+    let mut data = [0_u8; 3];
+    let mut frame = FrameMut::new(&mut data).unwrap();
+
+    frame.mode_mut().set(Mode::Low);
+    frame.other_mut().set(Mode::Max);
+    frame.ty_mut().set(EtherType::Arp);
+    assert_eq!(data, [0x15, 0x08, 0x06]);
+
+    let mut frame = FrameMut::new(&mut data).unwrap();
+    frame.ty_mut().set(EtherType::Unknown(0xbeef));
+    assert_eq!(data, [0x15, 0xbe, 0xef]);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BitfieldEnum)]
+pub enum Version {
+    Ipv4 = 4,
+    IPv4 = 6,
+}
+
+#[test]
+fn variants_differing_in_case() {
+    assert_eq!(Version::from_underlay(4), Version::Ipv4);
+    assert_eq!(Version::from_underlay(6), Version::IPv4);
+    assert_eq!(Version::into_underlay(Version::IPv4), 6);
+}
@@ -0,0 +1,87 @@
+use std::net::Ipv4Addr;
+
+use dmbf::{bitfield, BitfieldEnum};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BitfieldEnum)]
+pub enum Kind {
+    Data = 0,
+    Ack = 1,
+}
+
+#[bitfield(byte_order = "big", display)]
+struct Header {
+    #[bitfield(bits = 4, constant = 4)]
+    version: u8,
+    #[bitfield(bits = 2, reserved)]
+    _rsvd: u8,
+    #[bitfield(bits = 2)]
+    kind: Kind,
+    #[bitfield(bits = 1)]
+    urgent: bool,
+    #[bitfield(bits = 7)]
+    delta: i8,
+    src: Ipv4Addr,
+}
+
+/// A target without `Debug`
+pub struct Opaque(u8);
+
+impl From<u8> for Opaque {
+    fn from(v: u8) -> Self {
+        Self(v)
+    }
+}
+
+impl From<Opaque> for u8 {
+    fn from(v: Opaque) -> Self {
+        v.0
+    }
+}
+
+#[bitfield]
+struct NoDebug {
+    #[bitfield(bits = 8, from_into)]
+    opaque: Opaque,
+}
+
+#[test]
+fn debug_decodes_fields() {
+    let data = [0x41, 0xfe, 192, 168, 0, 1];
+    let header = Header::new(&data).unwrap();
+    assert_eq!(
+        format!("{header:?}"),
+        "Header { version: 4, kind: Ack, urgent: true, delta: -2, src: 192.168.0.1 }"
+    );
+    assert_eq!(format!("{:?}", header.delta()), "-2");
+
+    let mut data = data;
+    data[0] = 0x43;
+    let header = HeaderMut::new(&mut data).unwrap();
+    assert_eq!(
+        format!("{header:?}"),
+        "Header { version: 4, kind: InvalidValue { raw: 3 }, urgent: true, delta: -2, src: 192.168.0.1 }"
+    );
+
+    // Targets without `Debug` only lose the `Debug` of the struct
+    let data = [0x12];
+    assert_eq!(NoDebug::new(&data).unwrap().opaque().get().0, 0x12);
+}
+
+#[test]
+fn display_renders_table() {
+    let data = [0x41, 0xfe, 192, 168, 0, 1];
+    let header = Header::new(&data).unwrap();
+    assert_eq!(
+        header.to_string(),
+        "\
++---------+------+-------------+
+| Field   | Bits | Value       |
++---------+------+-------------+
+| version |    4 | 4           |
+| kind    |    2 | Ack         |
+| urgent  |    1 | true        |
+| delta   |    7 | -2          |
+| src     |   32 | 192.168.0.1 |
++---------+------+-------------+"
+    );
+}
@@ -2,9 +2,9 @@ use dmbf_impl::bitfield;
 
 #[test]
 fn hybrid_fields_u16() {
-    #[bitfield(endianness = dmbf::Endianness::Msb0)]
+    #[bitfield(byte_order = "big")]
     struct Foo {
-        #[bitfield(bits = 1, from_into, from = |v: u16| v as u8)]
+        #[bitfield(bits = 1, from = |v: u16| v as u8, into = |v: u8| v as u16)]
         a: u8,
         #[bitfield(bits = 15)]
         b: u16,
@@ -12,15 +12,15 @@ fn hybrid_fields_u16() {
 
     for i in 0_u16..=0xFFFF_u16 {
         let data: [u8; 2] = i.to_be_bytes();
-        let foo = Foo::from(&data);
+        let foo = Foo::new(&data).unwrap();
 
         assert_eq!(foo.a().get(), ((i & 0x8000) >> 15) as u8);
         assert_eq!(foo.b().get(), i & 0x7FFF);
     }
 
-    #[bitfield(endianness = dmbf::Endianness::Lsb0)]
+    #[bitfield(byte_order = "little")]
     struct Bar {
-        #[bitfield(bits = 1, from_into, from = |v: u16| v as u8)]
+        #[bitfield(bits = 1, from = |v: u16| v as u8, into = |v: u8| v as u16)]
         a: u8,
         #[bitfield(bits = 15)]
         b: u16,
@@ -28,7 +28,7 @@ fn hybrid_fields_u16() {
 
     for i in 0_u16..=0xFFFF_u16 {
         let data: [u8; 2] = i.to_be_bytes();
-        let bar = Bar::from(&data);
+        let bar = Bar::new(&data).unwrap();
 
         assert_eq!(bar.a().get(), ((i & 0x0080) >> 7) as u8);
         assert_eq!(bar.b().get(), ((i & 0xFF00) >> 8) | ((i & 0x007F) << 8));
@@ -37,19 +37,19 @@ fn hybrid_fields_u16() {
 
 #[test]
 fn hybrid_fields_u8_3() {
-    #[bitfield(endianness = dmbf::Endianness::Msb0)]
+    #[bitfield(byte_order = "big")]
     struct Foo {
-        #[bitfield(bits = 1, from = |v: [u8; 3]| v[2] as u8, into = |v: u8| [0, 0, v])]
+        #[bitfield(bits = 1, from = |v: [u8; 3]| v[2], into = |v: u8| [0, 0, v])]
         a: u8,
-        #[bitfield(bits = 2, from = |v: [u8; 3]| v[2] as u8, into = |v: u8| [0, 0, v])]
+        #[bitfield(bits = 2, from = |v: [u8; 3]| v[2], into = |v: u8| [0, 0, v])]
         b: u8,
-        #[bitfield(bits = 3, from = |v: [u8; 3]| v[2] as u8, into = |v: u8| [0, 0, v])]
+        #[bitfield(bits = 3, from = |v: [u8; 3]| v[2], into = |v: u8| [0, 0, v])]
         c: u8,
-        #[bitfield(bits = 5, from = |v: [u8; 3]| v[2] as u8, into = |v: u8| [0, 0, v])]
+        #[bitfield(bits = 5, from = |v: [u8; 3]| v[2], into = |v: u8| [0, 0, v])]
         d: u8,
-        #[bitfield(bits = 7, from = |v: [u8; 3]| v[2] as u8, into = |v: u8| [0, 0, v])]
+        #[bitfield(bits = 7, from = |v: [u8; 3]| v[2], into = |v: u8| [0, 0, v])]
         e: u8,
-        #[bitfield(bits = 6, from = |v: [u8; 3]| v[2] as u8, into = |v: u8| [0, 0, v])]
+        #[bitfield(bits = 6, from = |v: [u8; 3]| v[2], into = |v: u8| [0, 0, v])]
         f: u8,
     }
 
@@ -57,7 +57,7 @@ fn hybrid_fields_u8_3() {
         for j in 0..=0xFF {
             for k in 0..=0xFF {
                 let data: [u8; 3] = [i, j, k];
-                let foo = Foo::from(&data);
+                let foo = Foo::new(&data).unwrap();
 
                 assert_eq!(foo.a().get(), (i & 0x80) >> 7);
                 assert_eq!(foo.b().get(), (i & 0x60) >> 5);
@@ -65,3 +65,144 @@ fn hybrid_fields_u8_3() {
         }
     }
 }
+
+#[test]
+fn hybrid_fields_64_bits() {
+    #[bitfield(byte_order = "big")]
+    struct Foo {
+        #[bitfield(bits = 1)]
+        a: bool,
+        #[bitfield(bits = 63)]
+        b: u64,
+    }
+
+    let data: [u8; 8] = [0x80, 0, 0, 0, 0, 0, 0, 0x01];
+    let foo = Foo::new(&data).unwrap();
+    assert!(foo.a().get());
+    assert_eq!(foo.b().get(), 1);
+
+    let data: [u8; 8] = [0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+    let foo = Foo::new(&data).unwrap();
+    assert!(!foo.a().get());
+    assert_eq!(foo.b().get(), u64::MAX >> 1);
+}
+
+#[test]
+fn hybrid_fields_96_bits() {
+    #[bitfield(byte_order = "big")]
+    struct Descriptor {
+        #[bitfield(bits = 5)]
+        a: u8,
+        #[bitfield(bits = 27)]
+        b: u32,
+        #[bitfield(bits = 33)]
+        c: u64,
+        #[bitfield(bits = 31)]
+        d: u32,
+    }
+
+    let value: u128 = (0x15 << 91) | (0x0123_4567 << 64) | (0x1_89ab_cdef << 31) | 0x7654_3210;
+    let data: [u8; 12] = value.to_be_bytes()[4..].try_into().unwrap();
+    let desc = Descriptor::new(&data).unwrap();
+    assert_eq!(desc.a().get(), 0x15);
+    assert_eq!(desc.b().get(), 0x0123_4567);
+    assert_eq!(desc.c().get(), 0x1_89ab_cdef);
+    assert_eq!(desc.d().get(), 0x7654_3210);
+
+    let mut data = [0_u8; 12];
+    let mut desc = DescriptorMut::new(&mut data).unwrap();
+    desc.a_mut().set(0x15);
+    desc.b_mut().set(0x0123_4567);
+    desc.c_mut().set(0x1_89ab_cdef);
+    desc.d_mut().set(0x7654_3210);
+    assert_eq!(data, value.to_be_bytes()[4..]);
+}
+
+#[test]
+fn hybrid_fields_wider_than_128_bits() {
+    #[bitfield(byte_order = "big")]
+    struct Foo {
+        #[bitfield(bits = 3)]
+        a: u8,
+        #[bitfield(bits = 150)]
+        b: [u8; 19],
+        #[bitfield(bits = 7)]
+        c: u8,
+    }
+
+    let mut data = [0_u8; 20];
+    let mut foo = FooMut::new(&mut data).unwrap();
+    foo.a_mut().set(0x5);
+    foo.c_mut().set(0x7f);
+    assert_eq!(foo.b().get(), [0; 19]);
+    foo.b_mut().set([0xff; 19]);
+    assert_eq!(foo.a().get(), 0x5);
+    assert_eq!(foo.c().get(), 0x7f);
+
+    let mut expected = [0xff_u8; 19];
+    expected[0] = 0x3f;
+    assert_eq!(foo.b().get(), expected);
+    assert_eq!(data[0], 0xbf);
+    assert_eq!(data[19], 0xff);
+}
+
+#[test]
+fn hybrid_fields_pad() {
+    #[bitfield(byte_order = "big")]
+    struct Foo {
+        #[bitfield(bits = 3)]
+        a: u8,
+        #[bitfield(bits = 2, pad)]
+        b: u8,
+        c: u16,
+    }
+
+    let data = [0b1011_1101_u8, 0x12, 0x34];
+    let foo = Foo::new(&data).unwrap();
+    assert_eq!(core::mem::size_of::<foo::FieldBlock>(), 3);
+    assert_eq!(foo.a().get(), 0b101);
+    assert_eq!(foo.b().get(), 0b11);
+    assert_eq!(foo.c().get(), 0x1234);
+
+    // The reserved bits keep their value
+    let mut data = data;
+    let mut foo = FooMut::new(&mut data).unwrap();
+    foo.a_mut().set(0);
+    foo.b_mut().set(0);
+    assert_eq!(data[0], 0b0000_0101);
+
+    #[bitfield(bit_order = "lsb0")]
+    struct Bar {
+        #[bitfield(bits = 3)]
+        a: u8,
+        #[bitfield(bits = 2, pad)]
+        b: u8,
+    }
+
+    let data = [0b1011_1101_u8];
+    let bar = Bar::new(&data).unwrap();
+    assert_eq!(bar.a().get(), 0b101);
+    assert_eq!(bar.b().get(), 0b11);
+}
+
+#[test]
+fn hybrid_fields_auto_pad() {
+    #[bitfield(byte_order = "big", auto_pad)]
+    struct Foo {
+        #[bitfield(bits = 4)]
+        a: u8,
+        b: u8,
+        #[bitfield(bits = 12)]
+        c: u16,
+    }
+
+    let data = [0xa5_u8, 0x12, 0x34, 0x5f];
+    let foo = Foo::new(&data).unwrap();
+    assert_eq!(core::mem::size_of::<foo::FieldBlock>(), 4);
+    assert_eq!(foo.a().get(), 0xa);
+    assert_eq!(foo.b().get(), 0x12);
+    assert_eq!(foo.c().get(), 0x345);
+
+    let buf = FooBuf::default();
+    assert_eq!(buf.0, [0; 4]);
+}
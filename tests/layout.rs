@@ -0,0 +1,69 @@
+use std::net::Ipv4Addr;
+
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "big")]
+struct Ipv4 {
+    #[bitfield(bits = 4)]
+    version: u8,
+    #[bitfield(bits = 4)]
+    ihl: u8,
+    #[bitfield(bits = 6)]
+    dscp: u8,
+    #[bitfield(bits = 2)]
+    ecn: u8,
+    total_length: u16,
+    identification: u16,
+    #[bitfield(bits = 3)]
+    flags: u8,
+    #[bitfield(bits = 13)]
+    fragment_offset: u16,
+    ttl: u8,
+    protocol: u8,
+    checksum: u16,
+    src: Ipv4Addr,
+    dst: Ipv4Addr,
+}
+
+#[test]
+fn layout_constants() {
+    use ipv4::layout;
+
+    assert_eq!(layout::SIZE, 20);
+    assert_eq!(Ipv4::SIZE, 20);
+    assert_eq!(Ipv4Mut::SIZE, 20);
+    assert_eq!(Ipv4Buf::SIZE, 20);
+
+    assert_eq!(layout::version::BYTE_OFFSET, 0);
+    assert_eq!(layout::version::BIT_OFFSET, 4);
+    assert_eq!(layout::version::BIT_WIDTH, 4);
+    assert_eq!(layout::version::MASK, 0xf0);
+
+    assert_eq!(layout::ecn::BYTE_OFFSET, 1);
+    assert_eq!(layout::ecn::BIT_OFFSET, 0);
+    assert_eq!(layout::ecn::BIT_WIDTH, 2);
+    assert_eq!(layout::ecn::MASK, 0x03);
+
+    assert_eq!(layout::total_length::BYTE_OFFSET, 2);
+    assert_eq!(layout::total_length::BIT_WIDTH, 16);
+    assert_eq!(layout::total_length::MASK, 0xffff);
+
+    assert_eq!(layout::fragment_offset::BYTE_OFFSET, 6);
+    assert_eq!(layout::fragment_offset::BIT_OFFSET, 0);
+    assert_eq!(layout::fragment_offset::BIT_WIDTH, 13);
+    assert_eq!(layout::fragment_offset::MASK, 0x1fff);
+
+    assert_eq!(layout::checksum::BYTE_OFFSET, 10);
+    assert_eq!(layout::src::BYTE_OFFSET, 12);
+    assert_eq!(layout::src::BIT_WIDTH, 32);
+    assert_eq!(layout::dst::BYTE_OFFSET, 16);
+
+    // Usable in const context, e.g. for offset-based checksum updates
+    const CHECKSUM: core::ops::Range<usize> =
+        layout::checksum::BYTE_OFFSET..layout::checksum::BYTE_OFFSET + 2;
+    let data: [u8; 20] = [
+        0x45, 0x00, 0x00, 0x28, 0x00, 0x00, 0x40, 0x00, 0x40, 0x11, 0xb8, 0x0e, 0xc0, 0xa8, 0x00,
+        0x01, 0xc0, 0xa8, 0x00, 0xc7,
+    ];
+    assert_eq!(data[CHECKSUM], [0xb8, 0x0e]);
+}
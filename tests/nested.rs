@@ -0,0 +1,182 @@
+use dmbf::{bitfield, FieldInfo, MacAddr, RawValue, Visitor};
+
+#[bitfield(byte_order = "big")]
+struct Eth {
+    dst: MacAddr,
+    src: MacAddr,
+    ethertype: u16,
+}
+
+#[bitfield(byte_order = "big")]
+struct Vlan {
+    #[bitfield(bits = 3)]
+    pcp: u8,
+    #[bitfield(bits = 1)]
+    dei: bool,
+    #[bitfield(bits = 12, default = 1)]
+    vid: u16,
+    #[bitfield(constant = 0x0800)]
+    ethertype: u16,
+}
+
+#[bitfield(byte_order = "big")]
+struct VlanFrame {
+    /// Outer Ethernet header
+    #[bitfield(nested)]
+    eth: Eth,
+    #[bitfield(nested, required)]
+    vlan: Vlan,
+    ttl: u8,
+}
+
+mod proto {
+    use dmbf::bitfield;
+
+    #[bitfield]
+    pub struct Tag {
+        id: u8,
+    }
+}
+
+#[bitfield]
+struct Tagged {
+    #[bitfield(nested)]
+    tag: proto::Tag,
+    length: u8,
+}
+
+const FRAME: [u8; 19] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, // dst
+    0x00, 0x11, 0x22, 0x33, 0x44, 0x55, // src
+    0x81, 0x00, // ethertype
+    0xa0, 0x2a, // pcp, dei, vid
+    0x08, 0x00, // inner ethertype
+    0x40, // ttl
+];
+
+#[test]
+fn layout() {
+    use vlan_frame::layout;
+
+    assert_eq!(layout::SIZE, 19);
+    assert_eq!(layout::eth::BYTE_OFFSET, 0);
+    assert_eq!(layout::eth::BIT_WIDTH, 14 * 8);
+    assert_eq!(layout::vlan::BYTE_OFFSET, 14);
+    assert_eq!(layout::ttl::BYTE_OFFSET, 18);
+
+    let fields = VlanFrameBuf::FIELDS;
+    assert_eq!(fields.len(), 3);
+    assert_eq!(fields[0].doc, "Outer Ethernet header");
+    assert_eq!(fields[0].byte_len, 14);
+    assert_eq!(fields[0].fields, EthBuf::FIELDS);
+    assert_eq!(fields[1].fields[2].name, "vid");
+    assert!(fields[2].fields.is_empty());
+}
+
+#[test]
+fn accessors() {
+    let mut data = FRAME;
+    let mut frame = VlanFrameMut::new(&mut data).unwrap();
+    assert_eq!(frame.eth().dst().get(), MacAddr::BROADCAST);
+    assert_eq!(frame.eth().ethertype().get(), 0x8100);
+    assert_eq!(frame.vlan().pcp().get(), 5);
+    assert_eq!(frame.vlan().vid().get(), 42);
+    assert_eq!(frame.ttl().get(), 64);
+
+    frame.vlan_mut().vid_mut().set(7);
+    frame.eth_mut().src_mut().set(MacAddr::default());
+    assert_eq!(data[6..12], [0; 6]);
+    assert_eq!(data[14..16], [0xa0, 0x07]);
+}
+
+#[test]
+fn nested_by_path() {
+    let tagged = Tagged::new([0x12, 0x34]).unwrap();
+    assert_eq!(tagged.tag().id().get(), 0x12);
+    assert_eq!(tagged.length().get(), 0x34);
+    assert_eq!(tagged::layout::length::BYTE_OFFSET, 1);
+}
+
+#[test]
+fn nested_constants_are_checked() {
+    let mut data = FRAME;
+    data[16] = 0x86;
+    assert_eq!(
+        VlanFrame::new(&data).err(),
+        Some(dmbf::Error::Validation(dmbf::ValidationError {
+            field: "ethertype"
+        }))
+    );
+}
+
+#[test]
+fn defaults_and_builder() {
+    let buf = VlanFrameBuf::new();
+    assert_eq!(buf.as_view().vlan().vid().get(), 1);
+    assert_eq!(buf.as_view().vlan().ethertype().get(), 0x0800);
+
+    let buf = VlanFrameBuf::builder()
+        .vlan(VlanBuf::builder().pcp(5).vid(42).build())
+        .eth(Eth::new(&FRAME[..14]).unwrap().to_buf())
+        .ttl(64)
+        .build();
+    assert_eq!(buf.0, FRAME);
+}
+
+#[test]
+fn debug() {
+    let frame = VlanFrame::new(&FRAME).unwrap();
+    assert_eq!(
+        format!("{:?}", frame),
+        "VlanFrame { eth: Eth { dst: MacAddr([255, 255, 255, 255, 255, 255]), \
+         src: MacAddr([0, 17, 34, 51, 68, 85]), ethertype: 33024 }, \
+         vlan: Vlan { pcp: 5, dei: false, vid: 42, ethertype: 2048 }, ttl: 64 }"
+    );
+}
+
+/// Collects every field with its nesting depth
+#[derive(Default)]
+struct Tree {
+    depth: usize,
+    lines: Vec<String>,
+}
+
+impl Visitor for Tree {
+    fn visit_field(&mut self, info: &FieldInfo, value: RawValue<'_>) {
+        let RawValue::Int(value) = value else {
+            panic!("`{}` is not an integer", info.name);
+        };
+        let indent = "  ".repeat(self.depth);
+        self.lines.push(format!("{indent}{}={value:#x}", info.name));
+    }
+
+    fn enter_nested(&mut self, info: &FieldInfo) {
+        self.lines.push(format!("{}:", info.name));
+        self.depth += 1;
+    }
+
+    fn leave_nested(&mut self, _: &FieldInfo) {
+        self.depth -= 1;
+    }
+}
+
+#[test]
+fn visit() {
+    let mut tree = Tree::default();
+    dmbf::Visit::visit(&VlanFrame::new(&FRAME).unwrap(), &mut tree);
+    assert_eq!(
+        tree.lines,
+        [
+            "eth:",
+            "  dst=0xffffffffffff",
+            "  src=0x1122334455",
+            "  ethertype=0x8100",
+            "vlan:",
+            "  pcp=0x5",
+            "  dei=0x0",
+            "  vid=0x2a",
+            "  ethertype=0x800",
+            "ttl=0x40",
+        ]
+    );
+}
@@ -0,0 +1,38 @@
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "big")]
+struct Header {
+    #[bitfield(bits = 4, default = 4)]
+    version: u8,
+    #[bitfield(bits = 4, default = 5)]
+    ihl: u8,
+    #[bitfield(default = 64)]
+    ttl: u8,
+    protocol: u8,
+}
+
+#[test]
+fn default_applies_field_defaults() {
+    let buf = HeaderBuf::default();
+
+    assert_eq!(buf.0, [0x45, 64, 0]);
+    assert_eq!(buf.as_view().version().get(), 4);
+    assert_eq!(buf.as_view().ihl().get(), 5);
+    assert_eq!(buf.as_view().ttl().get(), 64);
+}
+
+#[test]
+fn buf_round_trips_through_views() {
+    let mut buf = HeaderBuf::default();
+    buf.as_view_mut().protocol_mut().set(17);
+
+    let copy = buf;
+    assert_eq!(copy, buf);
+    assert_eq!(<[u8; 3]>::from(copy), [0x45, 64, 17]);
+
+    let data: [u8; 4] = [0x46, 32, 6, 0xff];
+    let view = Header::new(&data).unwrap();
+    let buf = view.to_buf();
+    assert_eq!(buf, HeaderBuf::from([0x46, 32, 6]));
+    assert_ne!(buf, HeaderBuf::default());
+}
@@ -0,0 +1,110 @@
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "big")]
+struct Ipv4 {
+    #[bitfield(bits = 4)]
+    version: u8,
+    #[bitfield(bits = 4)]
+    ihl: u8,
+    tos: u8,
+    total_length: u16,
+    /// Bytes after the header, up to `total_length`
+    #[bitfield(payload, len = total_length - ihl * 4)]
+    payload: [u8],
+}
+
+#[bitfield(byte_order = "big")]
+struct Udp {
+    src_port: u16,
+    dst_port: u16,
+    #[bitfield(payload)]
+    body: &[u8],
+}
+
+fn packet() -> Vec<u8> {
+    // 4 byte header, 3 byte payload and 2 bytes of trailing padding
+    vec![0x41, 0x00, 0x00, 0x07, 0xaa, 0xbb, 0xcc, 0x00, 0x00]
+}
+
+#[test]
+fn bounded_payload() {
+    let data = packet();
+    let ipv4 = Ipv4::new(&data[..]).unwrap();
+    assert_eq!(ipv4.payload(), [0xaa, 0xbb, 0xcc]);
+
+    let mut ipv4 = Ipv4::new(packet()).unwrap();
+    ipv4.payload_mut().copy_from_slice(&[1, 2, 3]);
+    assert_eq!(ipv4.into_inner()[4..], [1, 2, 3, 0, 0]);
+}
+
+#[test]
+fn payload_length_is_checked() {
+    let data = packet();
+    assert_eq!(
+        Ipv4::new(&data[..6]).err(),
+        Some(dmbf::Error::Length(dmbf::LengthError {
+            expected: 7,
+            actual: 6
+        }))
+    );
+
+    // `ihl` describes a longer header than `total_length`
+    let mut data = packet();
+    data[0] = 0x43;
+    assert_eq!(
+        Ipv4::new(&data).err(),
+        Some(dmbf::Error::Validation(dmbf::ValidationError {
+            field: "payload"
+        }))
+    );
+}
+
+#[test]
+#[should_panic(expected = "payload `payload`: buffer too short")]
+fn header_change_past_buffer_panics() {
+    let mut ipv4 = Ipv4::new(packet()).unwrap();
+    ipv4.total_length_mut().set(100);
+    ipv4.payload();
+}
+
+#[test]
+fn checked_payload_access() {
+    let mut ipv4 = Ipv4::new(packet()).unwrap();
+    assert_eq!(ipv4.try_payload(), Ok(&[0xaa, 0xbb, 0xcc][..]));
+
+    ipv4.total_length_mut().set(0xffff);
+    assert_eq!(
+        ipv4.try_payload(),
+        Err(dmbf::Error::Length(dmbf::LengthError {
+            expected: 0xffff,
+            actual: 9
+        }))
+    );
+
+    ipv4.total_length_mut().set(2);
+    assert_eq!(
+        ipv4.try_payload_mut(),
+        Err(dmbf::Error::Validation(dmbf::ValidationError {
+            field: "payload"
+        }))
+    );
+
+    ipv4.total_length_mut().set(5);
+    ipv4.try_payload_mut().unwrap().copy_from_slice(&[1]);
+    assert_eq!(ipv4.into_inner()[4..], [1, 0xbb, 0xcc, 0, 0]);
+}
+
+#[test]
+fn unbounded_payload() {
+    let mut data = [0x12, 0x34, 0x00, 0x35, 0xde, 0xad];
+    let udp = Udp::new(&data).unwrap();
+    assert_eq!(udp.body(), [0xde, 0xad]);
+    assert_eq!(UdpBuf::SIZE, 4);
+
+    let mut udp = UdpMut::new(&mut data).unwrap();
+    udp.body_mut()[0] = 0xbe;
+    assert_eq!(data[4], 0xbe);
+
+    // An empty payload is fine
+    assert!(Udp::new(&data[..4]).unwrap().body().is_empty());
+}
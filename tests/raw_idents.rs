@@ -0,0 +1,33 @@
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "big")]
+struct Packet {
+    #[bitfield(bits = 4)]
+    r#type: u8,
+    #[bitfield(bits = 4, default = 1)]
+    r#match: u8,
+    r#loop: u16,
+}
+
+#[test]
+fn raw_idents() {
+    let buf = PacketBuf::builder().r#type(3).r#loop(0x0102).build();
+    assert_eq!(buf.0, [0x31, 0x01, 0x02]);
+
+    let packet = buf.as_view();
+    assert_eq!(packet.r#type().get(), 3);
+    assert_eq!(packet.r#match().get(), 1);
+    assert_eq!(buf.r#loop(), 0x0102);
+    assert_eq!(packet::layout::r#loop::BYTE_OFFSET, 1);
+    assert_eq!(Packet::FIELDS[0].name, "type");
+    assert_eq!(
+        format!("{packet:?}"),
+        "Packet { type: 3, match: 1, loop: 258 }"
+    );
+
+    let mut data = buf.0;
+    let mut packet = PacketMut::new(&mut data).unwrap();
+    packet.r#match_mut().set(2);
+    assert_eq!(data[0], 0x32);
+    assert_eq!(buf.with_type(4).r#type(), 4);
+}
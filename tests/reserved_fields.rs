@@ -0,0 +1,100 @@
+use dmbf::{bitfield, ValidationError};
+
+#[bitfield(byte_order = "big")]
+struct Control {
+    #[bitfield(bits = 1)]
+    enable: bool,
+    #[bitfield(bits = 3, reserved)]
+    _rsvd0: u8,
+    #[bitfield(bits = 4)]
+    mode: u8,
+    #[bitfield(reserved, must_be = 0)]
+    _rsvd1: u8,
+    #[bitfield(bits = 4, reserved, must_be = 0b1010)]
+    _rsvd2: u8,
+    #[bitfield(bits = 4)]
+    level: u8,
+}
+
+#[test]
+fn reserved_fields() {
+    let buf = ControlBuf::default();
+    assert_eq!(buf.0, [0x00, 0x00, 0xa0]);
+    assert_eq!(buf.as_view().validate(), Ok(()));
+
+    // Reserved bits are kept on writes to the other subfields
+    let mut data = [0x70_u8, 0x00, 0xa0];
+    let mut control = ControlMut::new(&mut data).unwrap();
+    control.enable_mut().set(true);
+    control.mode_mut().set(0x5);
+    control.level_mut().set(0xf);
+    assert_eq!(control.validate(), Ok(()));
+    assert_eq!(data, [0xf5, 0x00, 0xaf]);
+
+    data[1] = 0x01;
+    assert_eq!(
+        Control::new(&data).unwrap().validate(),
+        Err(ValidationError { field: "_rsvd1" })
+    );
+
+    data[1] = 0x00;
+    data[2] = 0x0f;
+    let err = Control::new(&data).unwrap().validate().unwrap_err();
+    assert_eq!(err.field, "_rsvd2");
+    assert_eq!(
+        err.to_string(),
+        "field `_rsvd2` does not hold its required value"
+    );
+}
+
+#[bitfield(byte_order = "big")]
+struct Wide {
+    #[bitfield(bits = 4, default = -1)]
+    offset: i8,
+    #[bitfield(bits = 8, reserved, must_be = 0x5a)]
+    _rsvd: u8,
+    #[bitfield(bits = 12, default = 0x123)]
+    id: u16,
+}
+
+#[test]
+fn values_of_a_24_bit_group() {
+    let buf = WideBuf::default();
+    assert_eq!(buf.0, [0xf5, 0xa1, 0x23]);
+
+    let wide = buf.as_view();
+    assert_eq!(wide.offset().get(), -1);
+    assert_eq!(wide.id().get(), 0x123);
+    assert_eq!(wide.validate(), Ok(()));
+
+    let mut data = buf.0;
+    let mut wide = WideMut::new(&mut data).unwrap();
+    wide.offset_mut().set(3);
+    wide.offset_mut().reset();
+    assert_eq!(wide.offset().get(), -1);
+
+    data[1] = 0xb1;
+    assert_eq!(
+        Wide::new(&data).unwrap().validate(),
+        Err(ValidationError { field: "_rsvd" })
+    );
+}
+
+#[bitfield]
+struct Split {
+    #[bitfield(reserved)]
+    #[bitfield(must_be = 0x5a)]
+    _rsvd: u8,
+    value: u8,
+}
+
+#[test]
+fn arguments_split_over_attributes() {
+    let buf = SplitBuf::default();
+    assert_eq!(buf.0, [0x5a, 0x00]);
+    assert_eq!(buf.as_view().validate(), Ok(()));
+    assert_eq!(
+        Split::new(&[0x00, 0x00]).unwrap().validate(),
+        Err(ValidationError { field: "_rsvd" })
+    );
+}
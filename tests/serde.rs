@@ -0,0 +1,151 @@
+#![cfg(feature = "serde")]
+
+use std::net::Ipv4Addr;
+
+use dmbf::{bitfield, BitfieldEnum, MacAddr};
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, BitfieldEnum)]
+pub enum Kind {
+    Data = 0,
+    Ack = 1,
+}
+
+impl serde::Serialize for Kind {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            Kind::Data => "data",
+            Kind::Ack => "ack",
+        })
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Kind {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "data" => Ok(Kind::Data),
+            "ack" => Ok(Kind::Ack),
+            other => Err(serde::de::Error::unknown_variant(other, &["data", "ack"])),
+        }
+    }
+}
+
+#[bitfield(byte_order = "big")]
+struct Header {
+    #[bitfield(bits = 4, constant = 4)]
+    version: u8,
+    #[bitfield(bits = 2, reserved)]
+    _rsvd: u8,
+    #[bitfield(bits = 2)]
+    kind: Kind,
+    #[bitfield(bits = 7)]
+    delta: i8,
+    #[bitfield(bits = 1)]
+    urgent: bool,
+    src: Ipv4Addr,
+    mac: MacAddr,
+}
+
+fn header() -> HeaderBuf {
+    HeaderBuf::builder()
+        .kind(Kind::Ack)
+        .delta(-3)
+        .urgent(true)
+        .src(Ipv4Addr::new(192, 168, 0, 1))
+        .mac(MacAddr::new(0x00, 0x11, 0x22, 0x33, 0x44, 0x55))
+        .build()
+}
+
+#[test]
+fn serialize() {
+    let buf = header();
+    let expected = json!({
+        "version": 4,
+        "kind": "ack",
+        "delta": -3,
+        "urgent": true,
+        "src": "192.168.0.1",
+        "mac": "00:11:22:33:44:55",
+    });
+
+    assert_eq!(serde_json::to_value(buf).unwrap(), expected);
+    assert_eq!(serde_json::to_value(buf.as_view()).unwrap(), expected);
+    let mut bytes = buf.0;
+    let view = HeaderMut::new(&mut bytes).unwrap();
+    assert_eq!(serde_json::to_value(view).unwrap(), expected);
+}
+
+#[test]
+fn serialize_keeps_declaration_order() {
+    let json = serde_json::to_string(&HeaderBuf::new()).unwrap();
+    assert_eq!(
+        json,
+        r#"{"version":4,"kind":"data","delta":0,"urgent":false,"src":"0.0.0.0","mac":"00:00:00:00:00:00"}"#
+    );
+}
+
+#[test]
+fn serialize_invalid_value() {
+    let mut buf = HeaderBuf::new();
+    buf.0[0] = 0x42;
+    let err = serde_json::to_string(&buf).unwrap_err();
+    assert_eq!(err.to_string(), "field `kind`: invalid raw value 0x2");
+}
+
+#[test]
+fn round_trip() {
+    let buf = header();
+    let json = serde_json::to_string(&buf).unwrap();
+    assert_eq!(serde_json::from_str::<HeaderBuf>(&json).unwrap(), buf);
+}
+
+#[test]
+fn deserialize_fills_defaults() {
+    let buf: HeaderBuf = serde_json::from_value(json!({ "delta": 5 })).unwrap();
+    let header = buf.as_view();
+    assert_eq!(header.version().get(), 4);
+    assert_eq!(header.kind().get(), Kind::Data);
+    assert_eq!(header.delta().get(), 5);
+    assert_eq!(header.src().get(), Ipv4Addr::UNSPECIFIED);
+}
+
+#[test]
+fn deserialize_errors() {
+    let err = serde_json::from_value::<HeaderBuf>(json!({ "delta": 64 })).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "field `delta`: value does not fit into 7 signed bits"
+    );
+
+    let err = serde_json::from_value::<HeaderBuf>(json!({ "version": 6 })).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "field `version` does not hold its required value"
+    );
+
+    let err = serde_json::from_value::<HeaderBuf>(json!({ "_rsvd": 1 })).unwrap_err();
+    assert!(err.to_string().starts_with("unknown field `_rsvd`"));
+}
+
+#[bitfield]
+struct Outer {
+    #[bitfield(nested)]
+    header: Header,
+    length: u8,
+}
+
+#[test]
+fn nested() {
+    let buf = OuterBuf::builder().header(header()).length(7).build();
+    let value = serde_json::to_value(buf).unwrap();
+    assert_eq!(value["header"]["delta"], -3);
+    assert_eq!(value["length"], 7);
+    assert_eq!(serde_json::from_value::<OuterBuf>(value).unwrap(), buf);
+
+    let err =
+        serde_json::from_value::<OuterBuf>(json!({ "header": { "version": 5 } })).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "field `version` does not hold its required value"
+    );
+}
@@ -0,0 +1,97 @@
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "big")]
+struct Imm {
+    #[bitfield(bits = 5)]
+    a: i8,
+    #[bitfield(bits = 3)]
+    b: u8,
+    #[bitfield(bits = 12)]
+    c: i16,
+    #[bitfield(bits = 4)]
+    d: u8,
+    #[bitfield(bits = 13)]
+    e: i16,
+    #[bitfield(bits = 3)]
+    f: u8,
+    #[bitfield(bits = 21)]
+    g: i32,
+    #[bitfield(bits = 3)]
+    h: u8,
+    i: i16,
+}
+
+#[test]
+fn sign_extension() {
+    let data: [u8; 10] = [0x80, 0x80, 0x00, 0x80, 0x00, 0x80, 0x00, 0x00, 0xff, 0xfe];
+    let imm = Imm::new(&data).unwrap();
+
+    assert_eq!(imm.a().get(), -16);
+    assert_eq!(imm.c().get(), -2048);
+    assert_eq!(imm.e().get(), -4096);
+    assert_eq!(imm.g().get(), -(1 << 20));
+    assert_eq!(imm.i().get(), -2);
+
+    assert_eq!(imm.b().get(), 0);
+    assert_eq!(imm.d().get(), 0);
+    assert_eq!(imm.f().get(), 0);
+    assert_eq!(imm.h().get(), 0);
+}
+
+#[test]
+fn round_trip_all_widths() {
+    let mut data = [0xff_u8; 10];
+
+    for v in -16..=15 {
+        let mut imm = ImmMut::new(&mut data).unwrap();
+        imm.a_mut().set(v);
+        assert_eq!(imm.a().get(), v);
+        assert_eq!(imm.b().get(), 0x7);
+    }
+    for v in -2048..=2047 {
+        let mut imm = ImmMut::new(&mut data).unwrap();
+        imm.c_mut().set(v);
+        assert_eq!(imm.c().get(), v);
+        assert_eq!(imm.d().get(), 0xf);
+    }
+    for v in -4096..=4095 {
+        let mut imm = ImmMut::new(&mut data).unwrap();
+        imm.e_mut().set(v);
+        assert_eq!(imm.e().get(), v);
+        assert_eq!(imm.f().get(), 0x7);
+    }
+    for v in (-(1 << 20)..(1 << 20)).step_by(997) {
+        let mut imm = ImmMut::new(&mut data).unwrap();
+        imm.g_mut().set(v);
+        assert_eq!(imm.g().get(), v);
+        assert_eq!(imm.h().get(), 0x7);
+    }
+}
+
+#[test]
+fn range_checking() {
+    let mut data = [0_u8; 10];
+    let mut imm = ImmMut::new(&mut data).unwrap();
+
+    assert!(imm.a_mut().try_set(15).is_ok());
+    assert!(imm.a_mut().try_set(-16).is_ok());
+    assert_eq!(
+        imm.a_mut().try_set(16),
+        Err(dmbf::RangeError {
+            bits: 5,
+            signed: true
+        })
+    );
+    assert!(imm.a_mut().try_set(-17).is_err());
+    assert!(imm.c_mut().try_set(2048).is_err());
+    assert!(imm.g_mut().try_set(1 << 20).is_err());
+    assert!(imm.b_mut().try_set(8).is_err());
+
+    // failed sets leave the field untouched
+    assert_eq!(imm.a().get(), -16);
+
+    // `set` truncates without touching the neighbours
+    imm.a_mut().set(33);
+    assert_eq!(imm.a().get(), 1);
+    assert_eq!(imm.b().get(), 0);
+}
@@ -0,0 +1,11 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(bits = 9)]
+    a: u8,
+    #[bitfield(bits = 7)]
+    b: u8,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(required)]
+    a: u8,
+    #[bitfield(required)]
+    b: u8,
+}
+
+fn main() {
+    let _ = Foo::builder().b(1).build();
+}
@@ -0,0 +1,13 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(constant = 4)]
+    version: u8,
+}
+
+fn main() {
+    let mut data = [4_u8; 1];
+    let mut foo = FooMut::new(&mut data).unwrap();
+    foo.version_mut().set(5);
+}
@@ -0,0 +1,8 @@
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "big", default = 1)]
+struct Foo {
+    a: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(from_into, from = |v: u8| v)]
+    a: u8,
+}
+
+fn main() {}
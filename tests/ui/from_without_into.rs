@@ -0,0 +1,9 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(from = |v: u8| v)]
+    a: u8,
+}
+
+fn main() {}
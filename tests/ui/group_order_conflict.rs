@@ -0,0 +1,11 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(bits = 4, byte_order = "big")]
+    a: u8,
+    #[bitfield(bits = 12, byte_order = "little")]
+    b: u16,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dmbf::{be, bitfield, le};
+
+#[bitfield]
+struct Foo {
+    #[bitfield(bits = 4)]
+    a: le::U16,
+    #[bitfield(bits = 12)]
+    b: be::U16,
+}
+
+fn main() {}
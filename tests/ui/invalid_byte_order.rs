@@ -0,0 +1,8 @@
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "middle")]
+struct Foo {
+    a: u8,
+}
+
+fn main() {}
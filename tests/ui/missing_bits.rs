@@ -0,0 +1,10 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(bits = 3)]
+    a: u8,
+    b: u8,
+}
+
+fn main() {}
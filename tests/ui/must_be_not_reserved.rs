@@ -0,0 +1,9 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(must_be = 0)]
+    a: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Outer {
+    #[bitfield(nested)]
+    inner: [u8; 2],
+}
+
+fn main() {}
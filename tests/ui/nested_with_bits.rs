@@ -0,0 +1,14 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Inner {
+    a: u8,
+}
+
+#[bitfield]
+struct Outer {
+    #[bitfield(nested, bits = 8)]
+    inner: Inner,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(pad)]
+    a: u8,
+}
+
+fn main() {}
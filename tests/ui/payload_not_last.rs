@@ -0,0 +1,10 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(payload)]
+    body: [u8],
+    a: u8,
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+use dmbf::underlay::RawFieldOps;
+use dmbf::{ByteOrder, RawField};
+
+#[derive(Clone, Copy, PartialEq)]
+struct Flags(u8);
+
+impl RawFieldOps for Flags {
+    fn not(&self) -> Self {
+        Flags(!self.0)
+    }
+    fn bitand(&self, rhs: Self) -> Self {
+        Flags(self.0 & rhs.0)
+    }
+    fn bitor(&self, rhs: Self) -> Self {
+        Flags(self.0 | rhs.0)
+    }
+    fn shl(&self, rhs: u32) -> Self {
+        Flags(self.0 << rhs)
+    }
+    fn shr(&self, rhs: u32) -> Self {
+        Flags(self.0 >> rhs)
+    }
+}
+
+impl RawField for Flags {
+    type Bytes = [bool; 8];
+
+    const ZERO: Self = Flags(0);
+    const MEMORY_ORDER: ByteOrder = ByteOrder::Big;
+
+    fn from_be_bytes(_: [bool; 8]) -> Self {
+        unimplemented!()
+    }
+    fn from_le_bytes(_: [bool; 8]) -> Self {
+        unimplemented!()
+    }
+    fn to_be_bytes(&self) -> [bool; 8] {
+        unimplemented!()
+    }
+    fn to_le_bytes(&self) -> [bool; 8] {
+        unimplemented!()
+    }
+}
+
+fn main() {}
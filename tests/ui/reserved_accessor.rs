@@ -0,0 +1,15 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(bits = 4)]
+    a: u8,
+    #[bitfield(bits = 4, reserved)]
+    rsvd: u8,
+}
+
+fn main() {
+    let data = [0_u8; 1];
+    let foo = Foo::new(&data).unwrap();
+    foo.rsvd();
+}
@@ -0,0 +1,13 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(from_into)]
+    #[bitfield(from = |v: u8| v, into = |v: u8| v)]
+    a: u8,
+    #[bitfield(constant = 1)]
+    #[bitfield(default = 2)]
+    b: u8,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(bits = 4, auto_pad)]
+    a: u8,
+    #[bitfield(display)]
+    b: u8,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo(u8, u16);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(bist = 4)]
+    a: u8,
+    #[bitfield(bits = 4)]
+    b: u8,
+}
+
+fn main() {}
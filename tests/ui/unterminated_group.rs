@@ -0,0 +1,12 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    a: u8,
+    #[bitfield(bits = 4)]
+    b: u8,
+    #[bitfield(bits = 1)]
+    c: bool,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use dmbf::bitfield;
+
+#[bitfield]
+struct Foo {
+    #[bitfield(bits = 4, default = 16)]
+    a: u8,
+    #[bitfield(bits = 4)]
+    b: u8,
+}
+
+fn main() {}
@@ -0,0 +1,107 @@
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "big")]
+struct Foo {
+    #[bitfield(bits = 4)]
+    a: u8,
+    #[bitfield(bits = 4)]
+    b: u8,
+    c: u8,
+    d: u8,
+    e: u16,
+    f: u32,
+}
+
+#[test]
+fn mut_view_writes_through() {
+    let mut data: [u8; 9] = [0x12, 0x34, 0x56, 0, 0, 0, 0, 0, 0];
+
+    let mut foo = FooMut::new(&mut data).unwrap();
+    assert_eq!(foo.a().get(), 0x1);
+    foo.a_mut().set(0x7);
+    foo.c_mut().set(0xab);
+
+    let foo: Foo<&[u8]> = foo.into();
+    assert_eq!(foo.a().get(), 0x7);
+    assert_eq!(foo.b().get(), 0x2);
+    assert_eq!(foo.c().get(), 0xab);
+    assert_eq!(foo.d().get(), 0x56);
+
+    assert_eq!(data, [0x72, 0xab, 0x56, 0, 0, 0, 0, 0, 0]);
+}
+
+#[test]
+fn read_view_shares_buffer() {
+    let data: [u8; 9] = [0x12, 0x34, 0x56, 0, 0, 0, 0, 0, 0];
+
+    let foo1 = Foo::new(&data).unwrap();
+    let foo2 = Foo::try_from(&data[..]).unwrap();
+    assert_eq!(foo1.c().get(), foo2.c().get());
+}
+
+#[test]
+fn short_buffer_is_rejected() {
+    let mut data: [u8; 9] = [0; 9];
+
+    let err = Foo::new(&data[..2]).err().unwrap();
+    assert_eq!(
+        err,
+        dmbf::Error::Length(dmbf::LengthError {
+            expected: 9,
+            actual: 2
+        })
+    );
+    assert!(FooMut::try_from(&mut data[..1]).is_err());
+
+    // Longer buffers are fine, the trailing bytes are ignored
+    let data: [u8; 10] = [0x12, 0x34, 0x56, 0, 0, 0, 0, 0, 0, 0x78];
+    let foo = Foo::new(&data).unwrap();
+    assert_eq!(foo.d().get(), 0x56);
+}
+
+#[test]
+fn view_at_odd_offset() {
+    let mut data = [0_u8; 32];
+
+    for offset in 0..8 {
+        let mut foo = FooMut::new(&mut data[offset..]).unwrap();
+        foo.e_mut().set(0x1234);
+        foo.f_mut().set(0x89ab_cdef);
+
+        let foo = Foo::new(&data[offset..]).unwrap();
+        assert_eq!(foo.e().get(), 0x1234);
+        assert_eq!(foo.f().get(), 0x89ab_cdef);
+        assert_eq!(data[offset + 3..offset + 5], [0x12, 0x34]);
+    }
+}
+
+/// Parses a header out of a buffer the caller hands over
+fn parse(data: Vec<u8>) -> Result<Foo<Vec<u8>>, dmbf::Error> {
+    Foo::new(data)
+}
+
+#[test]
+fn owned_buffers() {
+    let mut foo = parse(vec![0x12, 0x34, 0x56, 0, 0, 0, 0, 0, 0]).unwrap();
+    assert_eq!(foo.c().get(), 0x34);
+    foo.c_mut().set(0xab);
+    assert_eq!(foo.into_inner(), [0x12, 0xab, 0x56, 0, 0, 0, 0, 0, 0]);
+
+    let mut foo = Foo::new([0_u8; 9]).unwrap();
+    foo.e_mut().set(0x1234);
+    assert_eq!(foo.data[3..5], [0x12, 0x34]);
+
+    let foo = Foo::new(Box::<[u8]>::from(foo.data)).unwrap();
+    assert_eq!(foo.e().get(), 0x1234);
+    assert_eq!(foo.to_buf().0[3..5], [0x12, 0x34]);
+
+    assert!(parse(vec![0; 8]).is_err());
+}
+
+#[test]
+#[should_panic]
+fn shrunk_buffer_panics() {
+    let mut foo = Foo::new(vec![0; 9]).unwrap();
+    foo.data.truncate(4);
+    foo.e().get();
+}
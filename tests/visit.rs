@@ -0,0 +1,93 @@
+use dmbf::{bitfield, ByteOrder, FieldInfo, RawValue, Visit, Visitor};
+
+#[bitfield(byte_order = "big")]
+struct Header {
+    /// Protocol version
+    #[bitfield(bits = 4)]
+    version: u8,
+    #[bitfield(bits = 3, reserved)]
+    _rsvd: u8,
+    /// Set on the last fragment
+    ///
+    /// Never set on a single packet.
+    #[bitfield(bits = 1)]
+    last: bool,
+    length: dmbf::le::U16,
+    id: u128,
+}
+
+#[test]
+fn fields() {
+    assert_eq!(Header::FIELDS.len(), 5);
+    assert_eq!(HeaderBuf::FIELDS, header::layout::FIELDS);
+    assert_eq!(<HeaderMut as Visit>::FIELDS, header::layout::FIELDS);
+
+    assert_eq!(
+        Header::FIELDS[0],
+        FieldInfo {
+            name: "version",
+            byte_offset: 0,
+            byte_len: 1,
+            bit_offset: 4,
+            bit_width: 4,
+            byte_order: ByteOrder::Big,
+            reserved: false,
+            doc: "Protocol version",
+            fields: &[],
+        }
+    );
+    assert_eq!(Header::FIELDS[1].name, "_rsvd");
+    assert!(Header::FIELDS[1].reserved);
+    assert_eq!(Header::FIELDS[1].doc, "");
+    assert_eq!(Header::FIELDS[2].bit_offset, 0);
+    assert_eq!(
+        Header::FIELDS[2].doc,
+        "Set on the last fragment\n\nNever set on a single packet."
+    );
+    assert_eq!(Header::FIELDS[3].byte_offset, 1);
+    assert_eq!(Header::FIELDS[3].byte_order, ByteOrder::Little);
+    assert_eq!(Header::FIELDS[4].byte_len, 16);
+    assert_eq!(Header::FIELDS[4].bit_width, 128);
+}
+
+/// Collects every field as `name=value`
+struct Dump(Vec<String>);
+
+impl Visitor for Dump {
+    fn visit_field(&mut self, info: &FieldInfo, value: RawValue<'_>) {
+        let value = match value {
+            RawValue::Int(v) => format!("{v:#x}"),
+            RawValue::Bytes(bytes) => format!("{bytes:02x?}"),
+        };
+        self.0.push(format!("{}={}", info.name, value));
+    }
+}
+
+#[test]
+fn visit() {
+    let mut data = [0u8; 19];
+    data[0] = 0x4b;
+    data[1..3].copy_from_slice(&[0x34, 0x12]);
+    data[18] = 0x01;
+    let header = Header::new(&data).unwrap();
+
+    let mut dump = Dump(Vec::new());
+    header.visit(&mut dump);
+    assert_eq!(
+        dump.0,
+        [
+            "version=0x4",
+            "_rsvd=0x5",
+            "last=0x1",
+            "length=0x1234",
+            &format!("id={:02x?}", 1u128.to_be_bytes()),
+        ]
+    );
+
+    // Closures are visitors as well, and every view visits the same fields
+    let mut names = Vec::new();
+    header
+        .to_buf()
+        .visit(&mut |info: &FieldInfo, _: RawValue<'_>| names.push(info.name));
+    assert_eq!(names, ["version", "_rsvd", "last", "length", "id"]);
+}
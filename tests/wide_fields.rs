@@ -0,0 +1,81 @@
+use dmbf::bitfield;
+
+#[bitfield(byte_order = "big")]
+struct Wide {
+    addr: [u8; 16],
+    #[bitfield(bits = 256)]
+    hash: [u8; 32],
+    #[bitfield(bits = 72)]
+    tag: [u8; 9],
+    #[bitfield(bits = 96)]
+    counter: u128,
+    id: u128,
+    pair: [u8; 2],
+}
+
+#[test]
+fn wide_fields() {
+    let mut data = [0_u8; 16 + 32 + 9 + 12 + 16 + 2];
+    for (i, b) in data.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+
+    let wide = Wide::new(&data).unwrap();
+    assert_eq!(wide.addr().get(), core::array::from_fn(|i| i as u8));
+    assert_eq!(wide.hash().get(), core::array::from_fn(|i| (16 + i) as u8));
+    assert_eq!(wide.tag().get(), core::array::from_fn(|i| (48 + i) as u8));
+    assert_eq!(
+        wide.counter().get(),
+        u128::from_be_bytes(core::array::from_fn(|i| if i < 4 {
+            0
+        } else {
+            (57 - 4 + i) as u8
+        }))
+    );
+    assert_eq!(
+        wide.id().get(),
+        u128::from_be_bytes(core::array::from_fn(|i| (69 + i) as u8))
+    );
+    assert_eq!(wide.pair().get(), [85, 86]);
+}
+
+#[test]
+fn wide_fields_set() {
+    let mut data = [0_u8; 87];
+    let mut wide = WideMut::new(&mut data).unwrap();
+
+    wide.hash_mut().set([0xaa; 32]);
+    wide.counter_mut().set(u128::MAX);
+    assert_eq!(wide.counter().get(), (1 << 96) - 1);
+    assert!(wide.counter_mut().try_set(1 << 96).is_err());
+    wide.id_mut().set(0x0102);
+
+    assert_eq!(data[16..48], [0xaa; 32]);
+    assert_eq!(data[57..69], [0xff; 12]);
+    assert_eq!(data[83..85], [0x01, 0x02]);
+    assert_eq!(data[..16], [0; 16]);
+    assert_eq!(data[48..57], [0; 9]);
+}
+
+#[bitfield]
+struct Digest {
+    id: u16,
+    #[bitfield(bits = 24)]
+    tag: [u8; 3],
+    hash: [u8; 4],
+}
+
+#[test]
+fn byte_arrays_keep_memory_order() {
+    let data = [0x01, 0x02, 0x0a, 0x0b, 0x0c, 0xde, 0xad, 0xbe, 0xef];
+    let digest = Digest::new(&data).unwrap();
+
+    assert_eq!(digest.id().get(), 0x0201);
+    assert_eq!(digest.tag().get(), [0x0a, 0x0b, 0x0c]);
+    assert_eq!(digest.hash().get(), [0xde, 0xad, 0xbe, 0xef]);
+
+    let mut data = [0_u8; 9];
+    let mut digest = DigestMut::new(&mut data).unwrap();
+    digest.hash_mut().set([1, 2, 3, 4]);
+    assert_eq!(data[5..], [1, 2, 3, 4]);
+}